@@ -1,23 +1,172 @@
 use anchor_lang::prelude::*;
 use anchor_lang::{
-    solana_program::{clock, program::invoke_signed},
-    system_program,
+    solana_program::{
+        clock, ed25519_program,
+        log::sol_log_compute_units,
+        program::{invoke, invoke_signed},
+        program_option::COption,
+        sysvar,
+    },
+    system_program, Discriminator,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, AssociatedToken},
+    token::{Mint, Token, TokenAccount},
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
 
 declare_id!("HWTkSSJhPQfipAd6QBkXPSypwz1tqBXDXpkdmkxNDcUJ");
 
+const PLATFORM_SEED: &[u8] = b"airdrop_platform";
+const MAX_METADATA_URI_LEN: usize = 200;
+const MAX_CATEGORY_LEN: usize = 32;
+const MAX_CAMPAIGN_ID_LEN: usize = 32;
+const MIN_START_DELAY: u64 = 60;
+const MAX_SCHEDULE_HORIZON: u64 = 365 * 24 * 60 * 60;
+const PLATFORM_SPACE: usize = 10240;
+const SCHEMA_VERSION: u8 = 3;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const MAX_BATCH_CAMPAIGNS: usize = 5;
+const MAX_BATCH_SIZE: usize = 16;
+const MAX_OPERATORS: usize = 16;
+const MAX_RECENT_WITHDRAWALS: usize = 20;
+const MAX_PURGED_CAMPAIGNS: usize = 32;
+const MAX_RECIPIENT_CHUNKS: usize = 8;
+const RECIPIENT_LIST_SPACE: usize =
+    8 + 4 + MAX_RECIPIENT_CHUNKS * (4 + MAX_BATCH_SIZE * 40) + 4 + 1;
+const RECEIPT_SPACE: usize = 8 + (4 + 8) + 8 + 1 + 8;
+#[cfg(all(target_arch = "bpf", not(feature = "no-entrypoint")))]
+const MAX_HEAP_LENGTH: usize = 256 * 1024;
+
+/// Bump allocator growing upwards from the start of the heap. The default one grows downwards
+/// from the end of the default 32 KiB heap, so it cannot use a larger heap frame requested by
+/// the transaction, which platforms holding many campaigns need to be deserialized.
+#[cfg(all(target_arch = "bpf", not(feature = "no-entrypoint")))]
+struct UpwardBumpAllocator;
+
+#[cfg(all(target_arch = "bpf", not(feature = "no-entrypoint")))]
+unsafe impl std::alloc::GlobalAlloc for UpwardBumpAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        // The heap starts zeroed, its first word keeps the position of the next allocation
+        let heap_start = anchor_lang::solana_program::entrypoint::HEAP_START_ADDRESS as usize;
+        let position = heap_start as *mut usize;
+        if *position == 0 {
+            *position = heap_start + std::mem::size_of::<usize>();
+        }
+        let start = (*position + layout.align() - 1) & !(layout.align() - 1);
+        match start.checked_add(layout.size()) {
+            Some(end) if end <= heap_start + MAX_HEAP_LENGTH => {
+                *position = end;
+                start as *mut u8
+            }
+            _ => std::ptr::null_mut(),
+        }
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: std::alloc::Layout) {}
+}
+
+#[cfg(all(target_arch = "bpf", not(feature = "no-entrypoint")))]
+#[global_allocator]
+static ALLOCATOR: UpwardBumpAllocator = UpwardBumpAllocator;
+
 #[program]
 pub mod playlink_airdrop {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, fee_per_asset: u64) -> Result<()> {
-        let airdrop_platform = &mut ctx.accounts.airdrop_platform;
-        airdrop_platform.admin = ctx.accounts.admin.key();
-        airdrop_platform.fee_per_asset = fee_per_asset;
-        airdrop_platform.operators.push(ctx.accounts.admin.key());
-        airdrop_platform.bump = *ctx.bumps.get("airdrop_platform").unwrap();
-        Ok(())
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        platform_id: String,
+        fee_per_asset: u64,
+        min_fee_per_asset: u64,
+        max_fee_per_asset: u64,
+        max_total_campaigns: u32,
+    ) -> Result<()> {
+        init_platform(
+            &mut ctx.accounts.airdrop_platform,
+            ctx.accounts.admin.key(),
+            *ctx.bumps.get("airdrop_platform").unwrap(),
+            platform_id,
+            fee_per_asset,
+            min_fee_per_asset,
+            max_fee_per_asset,
+            max_total_campaigns,
+        )
+    }
+
+    pub fn initialize_if_needed(
+        ctx: Context<InitializeIfNeeded>,
+        platform_id: String,
+        fee_per_asset: u64,
+        min_fee_per_asset: u64,
+        max_fee_per_asset: u64,
+        max_total_campaigns: u32,
+    ) -> Result<()> {
+        // An initialized platform always has an admin, leave it untouched
+        if ctx.accounts.airdrop_platform.admin != Pubkey::default() {
+            return Ok(());
+        }
+
+        init_platform(
+            &mut ctx.accounts.airdrop_platform,
+            ctx.accounts.admin.key(),
+            *ctx.bumps.get("airdrop_platform").unwrap(),
+            platform_id,
+            fee_per_asset,
+            min_fee_per_asset,
+            max_fee_per_asset,
+            max_total_campaigns,
+        )
+    }
+
+    pub fn migrate_platform(ctx: Context<MigratePlatform>) -> Result<()> {
+        // Platforms created before the per-platform settings are smaller than the current space.
+        // Fees used to be every lamport above rent, count them before the rent grows.
+        let platform_info = ctx.accounts.airdrop_platform.to_account_info();
+        let is_legacy = platform_info.data_len() < PLATFORM_SPACE;
+        let legacy_fees = withdrawable_lamports(&platform_info)?;
+
+        // Grow the account to the current space, topping up its rent from the admin.
+        // The new space is zeroed, which is what fields appended to the platform deserialize from.
+        if is_legacy {
+            let rent = Rent::get()?;
+            transfer_to_platform(
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.admin.to_account_info(),
+                &platform_info,
+                rent.minimum_balance(PLATFORM_SPACE)
+                    - rent.minimum_balance(platform_info.data_len()),
+            )?;
+            platform_info.realloc(PLATFORM_SPACE, true)?;
+        }
+
+        // Legacy campaigns and assets have a layout of their own, they are converted one by one
+        let mut airdrop_platform = if is_legacy {
+            let data = platform_info.try_borrow_data()?;
+            require!(
+                data[..8] == AirdropPlatform::discriminator(),
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            LegacyAirdropPlatform::deserialize(&mut &data[8..])?.migrate(ctx.remaining_accounts)?
+        } else {
+            AirdropPlatform::try_deserialize(&mut &platform_info.try_borrow_data()?[..])?
+        };
+        require!(
+            ctx.accounts.admin.key() == airdrop_platform.admin,
+            PlaylinkAirdropErr::NotPlatformAdmin
+        );
+
+        // Already up to date, nothing to do
+        if airdrop_platform.schema_version >= SCHEMA_VERSION {
+            return Ok(());
+        }
+        if airdrop_platform.schema_version < 2 {
+            airdrop_platform.accumulated_fees = legacy_fees;
+        }
+        airdrop_platform.schema_version = SCHEMA_VERSION;
+
+        let mut data = platform_info.try_borrow_mut_data()?;
+        data.fill(0);
+        airdrop_platform.try_serialize(&mut &mut data[..])
     }
 
     pub fn set_operators(
@@ -30,10 +179,20 @@ pub mod playlink_airdrop {
             operators.len() == is_operators.len(),
             PlaylinkAirdropErr::LengthsMismatch
         );
+        require!(
+            operators.len() <= MAX_OPERATORS,
+            PlaylinkAirdropErr::TooManyOperators
+        );
 
-        // Add or remove operators
+        // Add or remove operators. The default pubkey, which is also the system program,
+        // and the platform itself can never sign as operators.
+        let platform_key = ctx.accounts.airdrop_platform.key();
         for (i, new_operator) in operators.iter().enumerate() {
             if *is_operators.get(i).unwrap() {
+                require!(
+                    *new_operator != Pubkey::default() && *new_operator != platform_key,
+                    PlaylinkAirdropErr::InvalidOperator
+                );
                 ctx.accounts
                     .airdrop_platform
                     .operators
@@ -43,308 +202,4147 @@ pub mod playlink_airdrop {
                     .airdrop_platform
                     .operators
                     .retain(|op| op.key() != new_operator.key());
+                ctx.accounts
+                    .airdrop_platform
+                    .operator_activity
+                    .retain(|activity| activity.operator != new_operator.key());
             }
         }
+
+        // Every operator is kept on the platform account and scanned on each check
+        require!(
+            ctx.accounts.airdrop_platform.operators.len() <= MAX_OPERATORS,
+            PlaylinkAirdropErr::TooManyOperators
+        );
+        Ok(())
+    }
+
+    pub fn clear_operators(ctx: Context<ClearOperators>) -> Result<()> {
+        // Remove every operator at once, the admin is always retained
+        let admin = ctx.accounts.admin.key();
+        let airdrop_platform = &mut ctx.accounts.airdrop_platform;
+        let removed_operators: Vec<Pubkey> = airdrop_platform
+            .operators
+            .iter()
+            .filter(|op| op.key() != admin)
+            .cloned()
+            .collect();
+        airdrop_platform.operators = vec![admin];
+        airdrop_platform
+            .operator_activity
+            .retain(|activity| activity.operator == admin);
+
+        emit!(OperatorsCleared {
+            admin,
+            removed_operators
+        });
+
+        Ok(())
+    }
+
+    pub fn set_fee_bounds(
+        ctx: Context<SetFeeBounds>,
+        min_fee_per_asset: u64,
+        max_fee_per_asset: u64,
+    ) -> Result<()> {
+        let airdrop_platform = &mut ctx.accounts.airdrop_platform;
+        require!(
+            min_fee_per_asset <= airdrop_platform.fee_per_asset
+                && airdrop_platform.fee_per_asset <= max_fee_per_asset,
+            PlaylinkAirdropErr::FeeOutOfBounds
+        );
+        airdrop_platform.min_fee_per_asset = min_fee_per_asset;
+        airdrop_platform.max_fee_per_asset = max_fee_per_asset;
+        Ok(())
+    }
+
+    pub fn set_edit_grace_period(
+        ctx: Context<SetEditGracePeriod>,
+        edit_grace_period: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_platform.edit_grace_period = edit_grace_period;
+        Ok(())
+    }
+
+    pub fn set_min_campaign_duration(
+        ctx: Context<SetMinCampaignDuration>,
+        min_campaign_duration: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_platform.min_campaign_duration = min_campaign_duration;
+        Ok(())
+    }
+
+    pub fn set_dust_threshold(ctx: Context<SetDustThreshold>, dust_threshold: u64) -> Result<()> {
+        ctx.accounts.airdrop_platform.dust_threshold = dust_threshold;
+        Ok(())
+    }
+
+    pub fn set_claim_fee(ctx: Context<SetClaimFee>, claim_fee: u64) -> Result<()> {
+        ctx.accounts.airdrop_platform.claim_fee = claim_fee;
+        Ok(())
+    }
+
+    pub fn set_debug_logging(ctx: Context<SetDebugLogging>, debug_logging: bool) -> Result<()> {
+        ctx.accounts.airdrop_platform.debug_logging = debug_logging;
+        Ok(())
+    }
+
+    pub fn set_keep_drained_campaigns(
+        ctx: Context<SetKeepDrainedCampaigns>,
+        keep_drained_campaigns: bool,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_platform.keep_drained_campaigns = keep_drained_campaigns;
+        Ok(())
+    }
+
+    pub fn set_max_total_campaigns(
+        ctx: Context<SetMaxTotalCampaigns>,
+        max_total_campaigns: u32,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_platform.max_total_campaigns = max_total_campaigns;
+        Ok(())
+    }
+
+    pub fn set_withdrawal_delay(
+        ctx: Context<SetWithdrawalDelay>,
+        withdrawal_delay: u64,
+    ) -> Result<()> {
+        // The delay can only be raised, otherwise a compromised admin could lift it and withdraw at once
+        require!(
+            withdrawal_delay >= ctx.accounts.airdrop_platform.withdrawal_delay,
+            PlaylinkAirdropErr::InvalidWithdrawalDelay
+        );
+        ctx.accounts.airdrop_platform.withdrawal_delay = withdrawal_delay;
         Ok(())
     }
 
     pub fn set_fee_per_asset(ctx: Context<SetFeePerAsset>, new_fee: u64) -> Result<()> {
+        require!(
+            ctx.accounts.airdrop_platform.min_fee_per_asset <= new_fee
+                && new_fee <= ctx.accounts.airdrop_platform.max_fee_per_asset,
+            PlaylinkAirdropErr::FeeOutOfBounds
+        );
         ctx.accounts.airdrop_platform.fee_per_asset = new_fee;
+        ctx.accounts
+            .airdrop_platform
+            .record_activity(ctx.accounts.operator.key())
+    }
+
+    pub fn set_fee_per_asset_per_day(
+        ctx: Context<SetFeePerAssetPerDay>,
+        new_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_platform.fee_per_asset_per_day = new_fee;
+        Ok(())
+    }
+
+    pub fn set_fee_bps(ctx: Context<SetFeeBps>, fee_bps: u16) -> Result<()> {
+        // Zero goes back to the flat SOL fee
+        require!(fee_bps <= 10000, PlaylinkAirdropErr::InvalidFeeBps);
+        ctx.accounts.airdrop_platform.fee_bps = fee_bps;
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_airdrop_campaign(
         ctx: Context<CreateAirdropCampaign>,
         campaign_id: String,
         assets: Vec<Asset>,
         starting_time: u64,
+        ending_time: u64,
+        metadata_uri: Option<String>,
+        category: Option<String>,
+        max_per_recipient: u64,
+        max_acceptable_fee: Option<u64>,
     ) -> Result<()> {
-        // Check if campaign exists
+        // The fee per asset is read once, so that the fee paid and the one stored always agree,
+        // and the creator may refuse to pay more than expected
+        let fee_per_asset = ctx.accounts.airdrop_platform.fee_per_asset;
+        let fee_per_asset_per_day = ctx.accounts.airdrop_platform.fee_per_asset_per_day;
+        let airdrop_fee = ctx.accounts.airdrop_platform.campaign_fee(
+            ctx.accounts.campaign_creator.key(),
+            fee_per_asset,
+            fee_per_asset_per_day,
+            assets.len(),
+            starting_time,
+            ending_time,
+        )?;
         require!(
-            ctx.accounts
-                .airdrop_platform
-                .all_campaigns
-                .iter()
-                .all(|c| c.campaign_id != campaign_id),
-            PlaylinkAirdropErr::CampaignAlreadyCreated
-        );
-
-        // Withdraw airdrop fee from campaign creator's wallet
-        let airdrop_fee = ctx.accounts.airdrop_platform.fee_per_asset * assets.len() as u64;
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.campaign_creator.to_account_info(),
-                    to: ctx.accounts.airdrop_platform.to_account_info(),
-                },
-            ),
+            max_acceptable_fee.map_or(true, |max_fee| airdrop_fee <= max_fee),
+            PlaylinkAirdropErr::FeeExceedsMax
+        );
+
+        // Withdraw airdrop fee and the SOL to airdrop from campaign creator's wallet, if there is any
+        transfer_to_platform(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.campaign_creator.to_account_info(),
+            &ctx.accounts.airdrop_platform.to_account_info(),
+            airdrop_fee + sol_amount(&assets)?,
+        )?;
+
+        add_campaign(
+            &mut ctx.accounts.airdrop_platform,
+            ctx.accounts.campaign_creator.key(),
+            campaign_id,
+            assets,
+            starting_time,
+            ending_time,
+            metadata_uri,
+            category,
+            max_per_recipient,
             airdrop_fee,
+            fee_per_asset,
+            fee_per_asset_per_day,
+            ctx.remaining_accounts,
         )?;
+        fit_account(
+            &ctx.accounts.airdrop_platform,
+            &ctx.accounts.campaign_creator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )
+    }
 
-        // Validate data
+    pub fn create_campaigns_batch(
+        ctx: Context<CreateCampaignsBatch>,
+        campaigns: Vec<CampaignInput>,
+    ) -> Result<()> {
+        // Keep the batch within the compute budget
         require!(
-            (clock::Clock::get().unwrap().unix_timestamp as u64) < starting_time,
-            PlaylinkAirdropErr::LowStartingTime
+            campaigns.len() <= MAX_BATCH_CAMPAIGNS,
+            PlaylinkAirdropErr::BatchTooLarge
         );
 
-        // Create new airdrop campaign
-        ctx.accounts
-            .airdrop_platform
-            .all_campaigns
-            .push(AirdropCampaign {
-                campaign_id: campaign_id.clone(),
-                creator: ctx.accounts.campaign_creator.key(),
-                assets: assets.clone(),
-                starting_time,
-                total_available_assets: assets.iter().map(|asset| asset.available_amount).sum(),
+        // Withdraw the airdrop fees and the SOL to airdrop of the whole batch at once
+        let fee_per_asset = ctx.accounts.airdrop_platform.fee_per_asset;
+        let fee_per_asset_per_day = ctx.accounts.airdrop_platform.fee_per_asset_per_day;
+        let mut airdrop_fees = Vec::with_capacity(campaigns.len());
+        let mut total_deposit = 0u64;
+        for campaign in &campaigns {
+            let airdrop_fee = ctx.accounts.airdrop_platform.campaign_fee(
+                ctx.accounts.campaign_creator.key(),
+                fee_per_asset,
+                fee_per_asset_per_day,
+                campaign.assets.len(),
+                campaign.starting_time,
+                campaign.ending_time,
+            )?;
+            total_deposit = total_deposit
+                .checked_add(airdrop_fee + sol_amount(&campaign.assets)?)
+                .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?;
+            airdrop_fees.push(airdrop_fee);
+        }
+        transfer_to_platform(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.campaign_creator.to_account_info(),
+            &ctx.accounts.airdrop_platform.to_account_info(),
+            total_deposit,
+        )?;
+
+        // Campaigns are created one after the other, so an id used twice in the batch
+        // is rejected just like an id that already exists
+        let mut mints = ctx.remaining_accounts;
+        for (campaign, airdrop_fee) in campaigns.into_iter().zip(airdrop_fees) {
+            let mint_count = campaign
+                .assets
+                .iter()
+                .filter(|asset| asset.kind != AssetKind::Sol)
+                .count();
+            require!(
+                mint_count <= mints.len(),
+                PlaylinkAirdropErr::LengthsMismatch
+            );
+            add_campaign(
+                &mut ctx.accounts.airdrop_platform,
+                ctx.accounts.campaign_creator.key(),
+                campaign.campaign_id,
+                campaign.assets,
+                campaign.starting_time,
+                campaign.ending_time,
+                campaign.metadata_uri,
+                campaign.category,
+                campaign.max_per_recipient,
                 airdrop_fee,
-            });
+                fee_per_asset,
+                fee_per_asset_per_day,
+                &mints[..mint_count],
+            )?;
+            mints = &mints[mint_count..];
+        }
+        fit_account(
+            &ctx.accounts.airdrop_platform,
+            &ctx.accounts.campaign_creator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )
+    }
 
-        emit!(AirdropCampaignCreated {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_airdrop_campaign_waived(
+        ctx: Context<CreateAirdropCampaignWaived>,
+        campaign_id: String,
+        assets: Vec<Asset>,
+        starting_time: u64,
+        ending_time: u64,
+        metadata_uri: Option<String>,
+        category: Option<String>,
+        max_per_recipient: u64,
+    ) -> Result<()> {
+        // First-party campaigns are created by operators without paying any fee,
+        // only the SOL to airdrop is deposited
+        transfer_to_platform(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.operator.to_account_info(),
+            &ctx.accounts.airdrop_platform.to_account_info(),
+            sol_amount(&assets)?,
+        )?;
+        add_campaign(
+            &mut ctx.accounts.airdrop_platform,
+            ctx.accounts.operator.key(),
             campaign_id,
-            creator: ctx.accounts.campaign_creator.key(),
-            assets: assets.clone(),
-            starting_time
-        });
-
-        Ok(())
+            assets,
+            starting_time,
+            ending_time,
+            metadata_uri,
+            category,
+            max_per_recipient,
+            0,
+            0,
+            0,
+            ctx.remaining_accounts,
+        )?;
+        fit_account(
+            &ctx.accounts.airdrop_platform,
+            &ctx.accounts.operator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update_campaign(
         ctx: Context<UpdateCampaign>,
         campaign_id: String,
         assets: Vec<Asset>,
         starting_time: u64,
+        ending_time: u64,
+        metadata_uri: Option<String>,
+        category: Option<String>,
+        max_per_recipient: u64,
     ) -> Result<()> {
-        let new_airdrop_fee = ctx.accounts.airdrop_platform.fee_per_asset * assets.len() as u64;
+        let edit_grace_period = ctx.accounts.airdrop_platform.edit_grace_period;
+        let min_campaign_duration = ctx.accounts.airdrop_platform.min_campaign_duration;
         let airdrop_platform = ctx.accounts.airdrop_platform.to_account_info();
+        require!(!assets.is_empty(), PlaylinkAirdropErr::NoAssets);
+        validate_mints_allowed(&assets, &ctx.accounts.airdrop_platform)?;
 
         // Make sure that this campaign exist
-        require!(
-            ctx.accounts
-                .airdrop_platform
-                .all_campaigns
-                .iter()
-                .any(|c| c.campaign_id == campaign_id),
-            PlaylinkAirdropErr::CampaignNotExists
-        );
-
-        // Only campaign creator can update
-        let campaign = ctx
+        let campaign_index = ctx
             .accounts
             .airdrop_platform
-            .all_campaigns
-            .iter_mut()
-            .find(|c| c.campaign_id == campaign_id)
-            .unwrap();
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only the change in assets and duration is charged on top of the fee stored on the campaign,
+        // at the rates that applied at creation. Campaigns that were exempt or charged in tokens at creation
+        // pay for the change once that no longer holds, operator-waived campaigns have zero rates and stay free.
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        let old_fee = ctx.accounts.airdrop_platform.campaign_fee(
+            campaign.creator,
+            campaign.fee_per_asset,
+            campaign.fee_per_asset_per_day,
+            campaign.assets.len(),
+            campaign.starting_time,
+            campaign.ending_time,
+        )?;
+        let new_fee = ctx.accounts.airdrop_platform.campaign_fee(
+            campaign.creator,
+            campaign.fee_per_asset,
+            campaign.fee_per_asset_per_day,
+            assets.len(),
+            starting_time,
+            ending_time,
+        )?;
+        let new_airdrop_fee = (campaign.airdrop_fee + new_fee).saturating_sub(old_fee);
+
+        // Only campaign creator can update, leaving deposited assets as they are
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
         require!(
             ctx.accounts.campaign_creator.key() == campaign.creator,
             PlaylinkAirdropErr::NotCampaignCreator
         );
+        require!(
+            !campaign.deposited || assets == campaign.assets,
+            PlaylinkAirdropErr::CampaignDeposited
+        );
 
-        // Make sure that this campaign has not started yet
+        // Make sure that this campaign has not started yet, or only just started
+        let now = current_timestamp()? as u64;
+        let edit_deadline = campaign
+            .starting_time
+            .checked_add(edit_grace_period)
+            .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?;
+        require!(now < edit_deadline, PlaylinkAirdropErr::UpdateNotAllowed);
+
+        // During the grace period after the start, only the metadata URI and category can be fixed
+        if now >= campaign.starting_time {
+            require!(
+                assets == campaign.assets
+                    && starting_time == campaign.starting_time
+                    && ending_time == campaign.ending_time
+                    && max_per_recipient == campaign.max_per_recipient,
+                PlaylinkAirdropErr::UpdateNotAllowed
+            );
+            validate_metadata_uri(&metadata_uri)?;
+            validate_category(&category)?;
+            campaign.metadata_uri = metadata_uri.clone();
+            campaign.category = category.clone();
+
+            emit!(AirdropCampaignUpdated {
+                campaign_id,
+                creator: campaign.creator.key(),
+                assets,
+                starting_time,
+                ending_time,
+                metadata_uri,
+                category
+            });
+
+            return Ok(());
+        }
+
+        // The creator delegated the campaign mints already, amounts may change but mints may not
         require!(
-            (clock::Clock::get().unwrap().unix_timestamp as u64) < campaign.starting_time,
-            PlaylinkAirdropErr::UpdateNotAllowed
+            token_mints(&assets) == token_mints(&campaign.assets),
+            PlaylinkAirdropErr::CannotChangeMints
         );
 
-        // Check airdrop fee and withdraw more if necessary
-        if new_airdrop_fee > campaign.airdrop_fee {
-            system_program::transfer(
-                CpiContext::new(
-                    ctx.accounts.system_program.to_account_info(),
-                    system_program::Transfer {
-                        from: ctx.accounts.campaign_creator.to_account_info(),
-                        to: airdrop_platform,
-                    },
-                ),
-                new_airdrop_fee - campaign.airdrop_fee,
+        // Check airdrop fee and deposited SOL, and withdraw more or refund the excess if necessary,
+        // leaving the creator's wallet untouched when neither changes. This happens before anything
+        // is edited, so that a creator unable to pay aborts the update cleanly.
+        let old_airdrop_fee = campaign.airdrop_fee;
+        let old_deposit = old_airdrop_fee + sol_amount(&campaign.assets)?;
+        let new_deposit = new_airdrop_fee + sol_amount(&assets)?;
+        if new_deposit > old_deposit {
+            require!(
+                ctx.accounts.campaign_creator.lamports() >= new_deposit - old_deposit,
+                PlaylinkAirdropErr::InsufficientFeeBalance
+            );
+            transfer_to_platform(
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.campaign_creator.to_account_info(),
+                &airdrop_platform,
+                new_deposit - old_deposit,
+            )?;
+        } else if new_deposit < old_deposit {
+            transfer_from_platform(
+                &airdrop_platform,
+                &ctx.accounts.campaign_creator.to_account_info(),
+                old_deposit - new_deposit,
             )?;
         }
 
         // Validate data
-        require!(
-            (clock::Clock::get().unwrap().unix_timestamp as u64) < starting_time,
-            PlaylinkAirdropErr::LowStartingTime
-        );
+        validate_starting_time(starting_time)?;
+        validate_ending_time(starting_time, ending_time, min_campaign_duration)?;
+        validate_metadata_uri(&metadata_uri)?;
+        validate_category(&category)?;
+        validate_asset_mints(&assets, ctx.remaining_accounts)?;
+        let assets = stable_asset_order(&campaign.assets, assets, campaign.total_distributed > 0)?;
+        let total_available_assets = total_amount(&assets)?;
 
         // Update campaign info
         campaign.assets = assets.clone();
         campaign.starting_time = starting_time;
-        campaign.total_available_assets = assets.iter().map(|asset| asset.available_amount).sum();
+        campaign.ending_time = ending_time;
+        campaign.total_available_assets = total_available_assets;
         campaign.airdrop_fee = new_airdrop_fee;
+        campaign.metadata_uri = metadata_uri.clone();
+        campaign.category = category.clone();
+        campaign.max_per_recipient = max_per_recipient;
+
+        // Keep the fee accounting in line with what was collected or refunded
+        let accumulated_fees = &mut ctx.accounts.airdrop_platform.accumulated_fees;
+        *accumulated_fees = (*accumulated_fees + new_airdrop_fee)
+            .checked_sub(old_airdrop_fee)
+            .ok_or(PlaylinkAirdropErr::InsufficientPlatformBalance)?;
 
         emit!(AirdropCampaignUpdated {
             campaign_id,
-            creator: campaign.creator.key(),
+            creator: ctx.accounts.campaign_creator.key(),
             assets: assets.clone(),
-            starting_time
+            starting_time,
+            ending_time,
+            metadata_uri,
+            category
         });
 
         Ok(())
     }
 
-    pub fn airdrop(ctx: Context<Airdrop>, campaign_id: String, asset_index: u64) -> Result<()> {
-        let airdrop_platform = ctx.accounts.airdrop_platform.clone();
+    pub fn reschedule_campaign(
+        ctx: Context<RescheduleCampaign>,
+        campaign_id: String,
+        new_starting_time: u64,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
 
-        // Make sure that the campaign exists
+        // Only campaign creator can reschedule
+        let min_campaign_duration = ctx.accounts.airdrop_platform.min_campaign_duration;
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
         require!(
-            ctx.accounts
-                .airdrop_platform
-                .all_campaigns
-                .iter()
-                .any(|c| c.campaign_id == campaign_id
-                    && c.creator == ctx.accounts.campaign_creator.key()),
-            PlaylinkAirdropErr::CampaignNotExists
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
         );
 
-        // Get the corresponding campaign
-        let campaign = ctx
+        // Make sure that this campaign has not started yet
+        let now = current_timestamp()? as u64;
+        require!(
+            now < campaign.starting_time,
+            PlaylinkAirdropErr::UpdateNotAllowed
+        );
+
+        // Validate data
+        validate_starting_time(new_starting_time)?;
+        validate_ending_time(
+            new_starting_time,
+            campaign.ending_time,
+            min_campaign_duration,
+        )?;
+
+        // Only the schedule changes, assets are left untouched
+        campaign.starting_time = new_starting_time;
+
+        emit!(AirdropCampaignUpdated {
+            campaign_id,
+            creator: campaign.creator.key(),
+            assets: campaign.assets.clone(),
+            starting_time: new_starting_time,
+            ending_time: campaign.ending_time,
+            metadata_uri: campaign.metadata_uri.clone(),
+            category: campaign.category.clone()
+        });
+
+        Ok(())
+    }
+
+    pub fn transfer_campaign(
+        ctx: Context<TransferCampaign>,
+        campaign_id: String,
+        new_creator: Pubkey,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
             .accounts
             .airdrop_platform
-            .all_campaigns
-            .iter_mut()
-            .find(|c| {
-                c.campaign_id == campaign_id && c.creator == ctx.accounts.campaign_creator.key()
-            })
-            .unwrap();
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Campaigns created before ids were bounded could not be airdropped from the new creator either
+        validate_campaign_id(&campaign_id)?;
 
-        // Make sure that this campaign has started
+        // Only campaign creator can hand the campaign over
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
         require!(
-            (clock::Clock::get().unwrap().unix_timestamp as u64) >= campaign.starting_time,
-            PlaylinkAirdropErr::CampaignNotStarts
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
         );
 
-        // Find corresponding assets
+        // Make sure that this campaign has not started yet
+        let now = current_timestamp()? as u64;
         require!(
-            asset_index < campaign.assets.len() as u64,
-            PlaylinkAirdropErr::InvalidAssetIndex
+            now < campaign.starting_time,
+            PlaylinkAirdropErr::UpdateNotAllowed
         );
-        let asset = campaign.assets.get_mut(asset_index as usize).unwrap();
+
+        // Tokens are airdropped from the creator's ATAs, so the new creator has to delegate
+        // them to the platform itself, a delegation cannot be made on someone else's behalf
+        let previous_creator = campaign.creator;
+        let mut campaign = ctx
+            .accounts
+            .airdrop_platform
+            .all_campaigns
+            .remove(campaign_index);
+        campaign.creator = new_creator;
+
+        // The campaign is keyed by its creator, so it moves among the new creator's campaigns
+        let new_index = match ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(new_creator, &campaign_id)
+        {
+            Ok(_) => return err!(PlaylinkAirdropErr::CampaignAlreadyCreated),
+            Err(index) => index,
+        };
+        ctx.accounts
+            .airdrop_platform
+            .all_campaigns
+            .insert(new_index, campaign);
+
+        emit!(CampaignCreatorChanged {
+            campaign_id,
+            previous_creator,
+            new_creator
+        });
+
+        Ok(())
+    }
+
+    pub fn top_up_asset(
+        ctx: Context<TopUpAsset>,
+        campaign_id: String,
+        asset_index: u64,
+        additional_amount: u64,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        let airdrop_platform = ctx.accounts.airdrop_platform.to_account_info();
+
+        // Only campaign creator can top up, even after the campaign has started.
+        // Deposited amounts are fixed, nothing would back the additional tokens.
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
         require!(
-            asset.asset_address == ctx.accounts.mint.key(),
-            PlaylinkAirdropErr::AssetAddressMismatch
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
         );
+        require!(!campaign.deposited, PlaylinkAirdropErr::CampaignDeposited);
+        let asset = campaign
+            .assets
+            .get_mut(asset_index as usize)
+            .ok_or(PlaylinkAirdropErr::InvalidAssetIndex)?;
 
-        // Airdrop - PDA signs by seeds and bump
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                &spl_token::ID,
-                ctx.accounts.creator_ata.to_account_info().key,
-                ctx.accounts.recipient_ata.to_account_info().key,
-                &airdrop_platform.key(),
-                &[&airdrop_platform.key()],
-                asset.available_amount,
-            )?,
-            &[
-                ctx.accounts.creator_ata.to_account_info(),
-                ctx.accounts.recipient_ata.to_account_info(),
-                airdrop_platform.to_account_info(),
-            ],
-            &[&[b"airdrop_platform", &[airdrop_platform.bump]]],
-        )?;
-
-        // Update status
-        campaign.total_available_assets -= asset.available_amount;
-        asset.available_amount = 0;
+        // An NFT asset always airdrops exactly one unit
+        require!(!asset.is_nft, PlaylinkAirdropErr::InvalidNftAsset);
 
-        // Remove campaign if all assets are airdropped
-        if campaign.total_available_assets == 0 {
-            ctx.accounts
-                .airdrop_platform
-                .all_campaigns
-                .retain(|c| c.campaign_id != campaign_id);
+        // SOL is held by the platform, so it has to be deposited along
+        if asset.kind == AssetKind::Sol {
+            transfer_to_platform(
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.campaign_creator.to_account_info(),
+                &airdrop_platform,
+                additional_amount,
+            )?;
         }
 
+        // Increase the asset amount along with the campaign total
+        asset.available_amount = asset
+            .available_amount
+            .checked_add(additional_amount)
+            .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?;
+        let available_amount = asset.available_amount;
+        campaign.total_available_assets = campaign
+            .total_available_assets
+            .checked_add(additional_amount)
+            .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?;
+
+        emit!(AssetToppedUp {
+            campaign_id,
+            asset_index,
+            additional_amount,
+            available_amount
+        });
+
         Ok(())
     }
 
-    pub fn withdraw_airdrop_fee(ctx: Context<WithdrawAirdropFee>) -> Result<()> {
-        let amount = ctx.accounts.airdrop_platform.to_account_info().lamports();
-        **ctx
+    pub fn set_recipient_cooldown(
+        ctx: Context<SetRecipientCooldown>,
+        campaign_id: String,
+        recipient_cooldown: u64,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
             .accounts
             .airdrop_platform
-            .to_account_info()
-            .try_borrow_mut_lamports()? -= amount;
-        **ctx
-            .accounts
-            .recipient
-            .to_account_info()
-            .try_borrow_mut_lamports()? += amount;
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can set the cooldown
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        campaign.recipient_cooldown = recipient_cooldown;
         Ok(())
     }
+
+    pub fn set_campaign_operators(
+        ctx: Context<SetCampaignOperators>,
+        campaign_id: String,
+        creator: Pubkey,
+        operators: Vec<Pubkey>,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(creator, &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator or the admin can hand a campaign over to its own operators,
+        // an empty list gives it back to the platform operators
+        let admin = ctx.accounts.airdrop_platform.admin;
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.authority.key() == campaign.creator
+                || ctx.accounts.authority.key() == admin,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        require!(
+            operators.len() <= MAX_OPERATORS,
+            PlaylinkAirdropErr::TooManyOperators
+        );
+        campaign.campaign_operators = operators;
+        Ok(())
+    }
+
+    pub fn set_allowed_distributors(
+        ctx: Context<SetAllowedDistributors>,
+        campaign_id: String,
+        distributors: Vec<Pubkey>,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can pick its distributors, an empty list allows every operator
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        require!(
+            distributors.len() <= MAX_OPERATORS,
+            PlaylinkAirdropErr::TooManyOperators
+        );
+        campaign.allowed_distributors = distributors;
+        Ok(())
+    }
+
+    pub fn set_claim_window(
+        ctx: Context<SetClaimWindow>,
+        campaign_id: String,
+        claim_window: u64,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can set the claim window
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        campaign.claim_window = claim_window;
+        Ok(())
+    }
+
+    pub fn set_freeze_on_airdrop(
+        ctx: Context<SetFreezeOnAirdrop>,
+        campaign_id: String,
+        freeze_on_airdrop: bool,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can require freezing
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        campaign.freeze_on_airdrop = freeze_on_airdrop;
+        Ok(())
+    }
+
+    pub fn set_require_creator_cosign(
+        ctx: Context<SetRequireCreatorCosign>,
+        campaign_id: String,
+        require_creator_cosign: bool,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can require co-signing
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        campaign.require_creator_cosign = require_creator_cosign;
+        Ok(())
+    }
+
+    pub fn set_min_holding(
+        ctx: Context<SetMinHolding>,
+        campaign_id: String,
+        min_holding_mint: Option<Pubkey>,
+        min_holding_amount: u64,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can restrict the campaign to holders
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        campaign.min_holding_mint = min_holding_mint;
+        campaign.min_holding_amount = min_holding_amount;
+        Ok(())
+    }
+
+    pub fn set_vesting(
+        ctx: Context<SetVesting>,
+        campaign_id: String,
+        vesting_start: u64,
+        vesting_end: u64,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can set a vesting schedule, and only before the campaign starts
+        let now = current_timestamp()? as u64;
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        require!(
+            now < campaign.starting_time,
+            PlaylinkAirdropErr::UpdateNotAllowed
+        );
+
+        // A zero end disables vesting
+        require!(
+            vesting_end == 0 || vesting_start < vesting_end,
+            PlaylinkAirdropErr::InvalidVestingSchedule
+        );
+        campaign.vesting_start = vesting_start;
+        campaign.vesting_end = vesting_end;
+        Ok(())
+    }
+
+    pub fn reauthorize_delegations<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReauthorizeDelegations<'info>>,
+        campaign_ids: Vec<String>,
+    ) -> Result<()> {
+        // One creator ATA is passed per token asset, in the order of the campaigns and their assets.
+        // Assets sharing an ATA are approved once, for their combined amount.
+        let mut creator_atas = ctx.remaining_accounts.iter();
+        let mut approvals: Vec<(AccountInfo<'info>, u64)> = Vec::new();
+        for campaign_id in campaign_ids.iter() {
+            let campaign_index = ctx
+                .accounts
+                .airdrop_platform
+                .find_campaign(ctx.accounts.campaign_creator.key(), campaign_id)
+                .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+            let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+            require!(
+                ctx.accounts.campaign_creator.key() == campaign.creator,
+                PlaylinkAirdropErr::NotCampaignCreator
+            );
+            for asset in campaign.assets.iter() {
+                let asset_mint = match asset.kind {
+                    AssetKind::Token { mint } => mint,
+                    AssetKind::Sol => continue,
+                };
+                let ata_info = creator_atas
+                    .next()
+                    .ok_or(PlaylinkAirdropErr::LengthsMismatch)?;
+                let creator_ata = Account::<TokenAccount>::try_from(ata_info)?;
+                require!(
+                    creator_ata.owner == campaign.creator && creator_ata.mint == asset_mint,
+                    PlaylinkAirdropErr::InvalidCreatorAta
+                );
+                match approvals
+                    .iter_mut()
+                    .find(|(info, _)| info.key() == ata_info.key())
+                {
+                    Some((_, amount)) => {
+                        *amount = amount
+                            .checked_add(asset.available_amount)
+                            .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?
+                    }
+                    None => approvals.push((ata_info.clone(), asset.available_amount)),
+                }
+            }
+        }
+
+        // The creator signs, consenting to delegate to the platform again
+        let platform_info = ctx.accounts.airdrop_platform.to_account_info();
+        let creator_info = ctx.accounts.campaign_creator.to_account_info();
+        for (creator_ata, amount) in approvals {
+            invoke(
+                &spl_token::instruction::approve(
+                    &spl_token::ID,
+                    creator_ata.key,
+                    platform_info.key,
+                    creator_info.key,
+                    &[],
+                    amount,
+                )?,
+                &[creator_ata, platform_info.clone(), creator_info.clone()],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn deposit_assets<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositAssets<'info>>,
+        campaign_id: String,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can deposit, and only once
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        require!(!campaign.deposited, PlaylinkAirdropErr::CampaignDeposited);
+
+        // Every token asset comes with the creator ATA, the mint and the platform vault of that mint.
+        // SOL assets were already deposited when the campaign was created.
+        let platform_key = ctx.accounts.airdrop_platform.key();
+        let creator_info = ctx.accounts.campaign_creator.to_account_info();
+        let mut token_accounts = ctx.remaining_accounts.chunks(3);
+        for asset in campaign.assets.iter() {
+            let asset_mint = match asset.kind {
+                AssetKind::Token { mint } => mint,
+                AssetKind::Sol => continue,
+            };
+            let [creator_ata, mint, vault] = match token_accounts.next() {
+                Some([creator_ata, mint, vault]) => [creator_ata, mint, vault],
+                _ => return err!(PlaylinkAirdropErr::LengthsMismatch),
+            };
+            require!(
+                mint.key() == asset_mint,
+                PlaylinkAirdropErr::AssetAddressMismatch
+            );
+            require!(
+                vault.key() == get_associated_token_address(&platform_key, &asset_mint),
+                PlaylinkAirdropErr::InvalidVault
+            );
+
+            // The creator signs, moving the tokens out of its own ATA
+            invoke(
+                &spl_token::instruction::transfer_checked(
+                    &spl_token::ID,
+                    creator_ata.key,
+                    mint.key,
+                    vault.key,
+                    creator_info.key,
+                    &[],
+                    asset.available_amount,
+                    asset.decimals,
+                )?,
+                &[
+                    creator_ata.clone(),
+                    mint.clone(),
+                    vault.clone(),
+                    creator_info.clone(),
+                ],
+            )?;
+        }
+        ctx.accounts.airdrop_platform.all_campaigns[campaign_index].deposited = true;
+
+        emit!(AssetsDeposited {
+            campaign_id,
+            creator: ctx.accounts.campaign_creator.key()
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_unused_deposit(
+        ctx: Context<WithdrawUnusedDeposit>,
+        campaign_id: String,
+        asset_index: u64,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can withdraw, and only what it deposited
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        require!(campaign.deposited, PlaylinkAirdropErr::CampaignNotDeposited);
+
+        // Recipients can count on the deposit while the campaign runs
+        let now = current_timestamp()? as u64;
+        require!(
+            now < campaign.starting_time
+                || (campaign.ending_time != 0 && now > campaign.ending_time),
+            PlaylinkAirdropErr::DepositLocked
+        );
+        let asset = campaign
+            .assets
+            .get_mut(asset_index as usize)
+            .ok_or(PlaylinkAirdropErr::InvalidAssetIndex)?;
+        require!(
+            asset.kind
+                == AssetKind::Token {
+                    mint: ctx.accounts.mint.key()
+                },
+            PlaylinkAirdropErr::AssetAddressMismatch
+        );
+
+        // Whatever was not airdropped leaves the campaign along with the vault
+        let amount = asset.available_amount;
+        let decimals = asset.decimals;
+        asset.available_amount = 0;
+        campaign.total_available_assets -= amount;
+        transfer_asset(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.creator_ata.to_account_info(),
+            &ctx.accounts.airdrop_platform,
+            amount,
+            decimals,
+        )?;
+
+        emit!(DepositWithdrawn {
+            campaign_id,
+            asset_index,
+            amount
+        });
+
+        Ok(())
+    }
+
+    pub fn unfreeze_recipient(ctx: Context<UnfreezeRecipient>) -> Result<()> {
+        set_account_frozen(
+            &ctx.accounts.recipient_ata.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.airdrop_platform,
+            false,
+        )
+    }
+
+    pub fn set_fee_exempt(ctx: Context<SetFeeExempt>, creator: Pubkey, exempt: bool) -> Result<()> {
+        let fee_exempt = &mut ctx.accounts.airdrop_platform.fee_exempt;
+        fee_exempt.retain(|exempt_creator| *exempt_creator != creator);
+        if exempt {
+            fee_exempt.push(creator);
+        }
+        Ok(())
+    }
+
+    pub fn set_mint_denied(ctx: Context<SetMintDenied>, mint: Pubkey, denied: bool) -> Result<()> {
+        let denied_mints = &mut ctx.accounts.airdrop_platform.denied_mints;
+        denied_mints.retain(|denied_mint| *denied_mint != mint);
+        if denied {
+            denied_mints.push(mint);
+        }
+        Ok(())
+    }
+
+    pub fn set_allowlist_enabled(
+        ctx: Context<SetAllowlistEnabled>,
+        allowlist_enabled: bool,
+    ) -> Result<()> {
+        ctx.accounts.airdrop_platform.allowlist_enabled = allowlist_enabled;
+        Ok(())
+    }
+
+    pub fn set_mint_allowed(
+        ctx: Context<SetMintAllowed>,
+        mint: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        let allowed_mints = &mut ctx.accounts.airdrop_platform.allowed_mints;
+        allowed_mints.retain(|allowed_mint| *allowed_mint != mint);
+        if allowed {
+            allowed_mints.push(mint);
+        }
+        Ok(())
+    }
+
+    pub fn set_campaign_paused(
+        ctx: Context<SetCampaignPaused>,
+        campaign_id: String,
+        creator: Pubkey,
+        paused: bool,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(creator, &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        ctx.accounts.airdrop_platform.all_campaigns[campaign_index].paused = paused;
+        Ok(())
+    }
+
+    pub fn admin_close_campaign<'info>(
+        ctx: Context<'_, '_, '_, 'info, AdminCloseCampaign<'info>>,
+        campaign_id: String,
+    ) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        require!(
+            ctx.accounts.campaign_creator.key()
+                == ctx.accounts.airdrop_platform.all_campaigns[campaign_index].creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+
+        // Operators may close any campaign, whoever created it
+        let campaign = ctx
+            .accounts
+            .airdrop_platform
+            .all_campaigns
+            .remove(campaign_index);
+
+        // Refund the fee unless it was earned already, along with the SOL left to airdrop.
+        // The creator's token delegation is deliberately left in place: only the creator can revoke it,
+        // and it is shared by all of the creator's campaigns on this platform, so revoking it would
+        // stall the others. Without the campaign, nothing airdrops from it on this campaign's behalf.
+        let refund = if campaign.fee_refundable(current_timestamp()? as u64) {
+            campaign.airdrop_fee
+        } else {
+            0
+        };
+        require!(
+            refund <= ctx.accounts.airdrop_platform.accumulated_fees,
+            PlaylinkAirdropErr::FeeAlreadyWithdrawn
+        );
+        ctx.accounts.airdrop_platform.accumulated_fees -= refund;
+
+        // Deposited tokens go back to the creator: every token asset left comes with
+        // the platform vault, the mint and the creator ATA of that mint
+        if campaign.deposited {
+            let platform_key = ctx.accounts.airdrop_platform.key();
+            let mut token_accounts = ctx.remaining_accounts.chunks(3);
+            for asset in campaign.assets.iter() {
+                let asset_mint = match asset.kind {
+                    AssetKind::Token { mint } if asset.available_amount > 0 => mint,
+                    _ => continue,
+                };
+                let [vault, mint, creator_ata] = match token_accounts.next() {
+                    Some([vault, mint, creator_ata]) => [vault, mint, creator_ata],
+                    _ => return err!(PlaylinkAirdropErr::LengthsMismatch),
+                };
+                require!(
+                    mint.key() == asset_mint,
+                    PlaylinkAirdropErr::AssetAddressMismatch
+                );
+                require!(
+                    vault.key() == get_associated_token_address(&platform_key, &asset_mint),
+                    PlaylinkAirdropErr::InvalidVault
+                );
+                require!(
+                    creator_ata.key()
+                        == get_associated_token_address(&campaign.creator, &asset_mint),
+                    PlaylinkAirdropErr::InvalidCreatorAta
+                );
+                transfer_asset(
+                    vault,
+                    mint,
+                    creator_ata,
+                    &ctx.accounts.airdrop_platform,
+                    asset.available_amount,
+                    asset.decimals,
+                )?;
+            }
+        }
+
+        let airdrop_platform = ctx.accounts.airdrop_platform.to_account_info();
+        transfer_from_platform(
+            &airdrop_platform,
+            &ctx.accounts.campaign_creator.to_account_info(),
+            refund + sol_amount(&campaign.assets)?,
+        )?;
+
+        emit!(CampaignForceClosed {
+            campaign_id,
+            closed_by: ctx.accounts.operator.key()
+        });
+
+        Ok(())
+    }
+
+    pub fn purge_completed(ctx: Context<PurgeCompleted>, older_than: i64) -> Result<()> {
+        // Completed campaigns only remain as records, drop those completed long enough ago, as campaigns
+        // without an ending time have none to go by. A bounded batch is removed per call, the admin
+        // calls again until none is left.
+        let cutoff = current_timestamp()?.saturating_sub(older_than);
+        let mut count = 0;
+        ctx.accounts
+            .airdrop_platform
+            .all_campaigns
+            .retain(|campaign| {
+                let purged = count < MAX_PURGED_CAMPAIGNS
+                    && campaign.completed
+                    && campaign.completed_at < cutoff;
+                count += purged as usize;
+                !purged
+            });
+
+        emit!(CampaignsPurged {
+            count: count as u32
+        });
+
+        Ok(())
+    }
+
+    pub fn split_campaign(
+        ctx: Context<SplitCampaign>,
+        source_id: String,
+        new_id: String,
+        asset_indices: Vec<u64>,
+    ) -> Result<()> {
+        // Make sure that the source campaign exist
+        let creator = ctx.accounts.campaign_creator.key();
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(creator, &source_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only a campaign that has not started can be split, deposited assets stay where they are
+        let now = current_timestamp()? as u64;
+        let source = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            now < source.starting_time,
+            PlaylinkAirdropErr::UpdateNotAllowed
+        );
+        require!(!source.deposited, PlaylinkAirdropErr::CampaignDeposited);
+
+        // Every listed asset moves once, and at least one asset stays behind
+        let mut asset_indices = asset_indices;
+        asset_indices.sort_unstable();
+        require!(
+            !asset_indices.is_empty() && asset_indices.len() < source.assets.len(),
+            PlaylinkAirdropErr::NoAssets
+        );
+        require!(
+            asset_indices.windows(2).all(|pair| pair[0] < pair[1])
+                && asset_indices[asset_indices.len() - 1] < source.assets.len() as u64,
+            PlaylinkAirdropErr::InvalidAssetIndex
+        );
+        let (moved, kept): (Vec<(usize, Asset)>, Vec<(usize, Asset)>) = source
+            .assets
+            .iter()
+            .copied()
+            .enumerate()
+            .partition(|(index, _)| asset_indices.contains(&(*index as u64)));
+        let moved: Vec<Asset> = moved.into_iter().map(|(_, asset)| asset).collect();
+        let kept: Vec<Asset> = kept.into_iter().map(|(_, asset)| asset).collect();
+        let starting_time = source.starting_time;
+        let ending_time = source.ending_time;
+        let metadata_uri = source.metadata_uri.clone();
+        let category = source.category.clone();
+        let max_per_recipient = source.max_per_recipient;
+
+        // The new campaign pays the fee of its own assets, the SOL to airdrop is already deposited
+        let fee_per_asset = ctx.accounts.airdrop_platform.fee_per_asset;
+        let fee_per_asset_per_day = ctx.accounts.airdrop_platform.fee_per_asset_per_day;
+        let airdrop_fee = ctx.accounts.airdrop_platform.campaign_fee(
+            creator,
+            fee_per_asset,
+            fee_per_asset_per_day,
+            moved.len(),
+            starting_time,
+            ending_time,
+        )?;
+        transfer_to_platform(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.campaign_creator.to_account_info(),
+            &ctx.accounts.airdrop_platform.to_account_info(),
+            airdrop_fee,
+        )?;
+
+        // Shrink the source before the new campaign is inserted, which may shift its position
+        let source = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        source.total_available_assets = total_amount(&kept)?;
+        source.assets = kept;
+        add_campaign(
+            &mut ctx.accounts.airdrop_platform,
+            creator,
+            new_id.clone(),
+            moved,
+            starting_time,
+            ending_time,
+            metadata_uri,
+            category,
+            max_per_recipient,
+            airdrop_fee,
+            fee_per_asset,
+            fee_per_asset_per_day,
+            ctx.remaining_accounts,
+        )?;
+        fit_account(
+            &ctx.accounts.airdrop_platform,
+            &ctx.accounts.campaign_creator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        emit!(CampaignSplit {
+            source_id,
+            new_id,
+            creator,
+            moved_assets: asset_indices.len() as u64
+        });
+
+        Ok(())
+    }
+
+    pub fn merge_campaigns(
+        ctx: Context<MergeCampaigns>,
+        target_id: String,
+        source_id: String,
+    ) -> Result<()> {
+        require!(
+            target_id != source_id,
+            PlaylinkAirdropErr::CannotMergeCampaign
+        );
+
+        // Both campaigns belong to the signing creator and have not started yet
+        let creator = ctx.accounts.campaign_creator.key();
+        let now = current_timestamp()? as u64;
+        let airdrop_platform = &mut ctx.accounts.airdrop_platform;
+        for campaign_id in [&target_id, &source_id] {
+            let campaign_index = airdrop_platform
+                .find_campaign(creator, campaign_id)
+                .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+            let campaign = &airdrop_platform.all_campaigns[campaign_index];
+            require!(
+                now < campaign.starting_time,
+                PlaylinkAirdropErr::UpdateNotAllowed
+            );
+            require!(!campaign.deposited, PlaylinkAirdropErr::CampaignDeposited);
+        }
+
+        // Remove the source first, the target position may shift
+        let source_index = airdrop_platform
+            .find_campaign(creator, &source_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        let source = airdrop_platform.all_campaigns.remove(source_index);
+        let target_index = airdrop_platform
+            .find_campaign(creator, &target_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Fungible assets of the same kind are summed up, the others appended
+        // so that the target assets keep their indices
+        let mut assets = airdrop_platform.all_campaigns[target_index].assets.clone();
+        for asset in source.assets {
+            match assets.iter_mut().find(|existing| {
+                !asset.is_nft
+                    && !existing.is_nft
+                    && existing.kind == asset.kind
+                    && existing.decimals == asset.decimals
+            }) {
+                Some(existing) => {
+                    existing.available_amount = existing
+                        .available_amount
+                        .checked_add(asset.available_amount)
+                        .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?;
+                }
+                None => assets.push(asset),
+            }
+        }
+
+        // The merged campaign is charged at the target rate, the fee paid for assets
+        // that were summed up goes back to the creator
+        let target = &airdrop_platform.all_campaigns[target_index];
+        let merged_fee = airdrop_platform.campaign_fee(
+            creator,
+            target.fee_per_asset,
+            target.fee_per_asset_per_day,
+            assets.len(),
+            target.starting_time,
+            target.ending_time,
+        )?;
+        let paid_fee = target.airdrop_fee + source.airdrop_fee;
+        let refunded_fee = paid_fee.saturating_sub(merged_fee);
+        let charged_fee = merged_fee.saturating_sub(paid_fee);
+        airdrop_platform.accumulated_fees = (airdrop_platform.accumulated_fees + charged_fee)
+            .checked_sub(refunded_fee)
+            .ok_or(PlaylinkAirdropErr::InsufficientPlatformBalance)?;
+        let target = &mut airdrop_platform.all_campaigns[target_index];
+        target.total_available_assets = total_amount(&assets)?;
+        target.assets = assets;
+        target.airdrop_fee = merged_fee;
+
+        let airdrop_platform = ctx.accounts.airdrop_platform.to_account_info();
+        if charged_fee > 0 {
+            transfer_to_platform(
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.campaign_creator.to_account_info(),
+                &airdrop_platform,
+                charged_fee,
+            )?;
+        } else if refunded_fee > 0 {
+            transfer_from_platform(
+                &airdrop_platform,
+                &ctx.accounts.campaign_creator.to_account_info(),
+                refunded_fee,
+            )?;
+        }
+
+        emit!(CampaignsMerged {
+            target_id,
+            source_id,
+            creator,
+            refunded_fee
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_campaign(ctx: Context<CancelCampaign>, campaign_id: String) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can cancel, and only before the campaign starts
+        let now = current_timestamp()? as u64;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        require!(
+            now < campaign.starting_time,
+            PlaylinkAirdropErr::UpdateNotAllowed
+        );
+        require!(
+            !campaign.holds_deposit(),
+            PlaylinkAirdropErr::DepositNotWithdrawn
+        );
+        let campaign = ctx
+            .accounts
+            .airdrop_platform
+            .all_campaigns
+            .remove(campaign_index);
+
+        // Nothing was airdropped, so the fee and the deposited SOL go back to the creator
+        let refunded_fee = campaign.airdrop_fee;
+        require!(
+            refunded_fee <= ctx.accounts.airdrop_platform.accumulated_fees,
+            PlaylinkAirdropErr::FeeAlreadyWithdrawn
+        );
+        ctx.accounts.airdrop_platform.accumulated_fees -= refunded_fee;
+        let airdrop_platform = ctx.accounts.airdrop_platform.to_account_info();
+        transfer_from_platform(
+            &airdrop_platform,
+            &ctx.accounts.campaign_creator.to_account_info(),
+            refunded_fee + sol_amount(&campaign.assets)?,
+        )?;
+
+        emit!(CampaignCancelled {
+            campaign_id,
+            creator: campaign.creator,
+            refunded_fee
+        });
+
+        Ok(())
+    }
+
+    pub fn reclaim_campaign(ctx: Context<ReclaimCampaign>, campaign_id: String) -> Result<()> {
+        // Make sure that this campaign exist
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+
+        // Only campaign creator can reclaim, and only once the campaign has ended
+        let now = current_timestamp()? as u64;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            ctx.accounts.campaign_creator.key() == campaign.creator,
+            PlaylinkAirdropErr::NotCampaignCreator
+        );
+        require!(
+            campaign.ending_time != 0 && now > campaign.ending_time,
+            PlaylinkAirdropErr::CampaignNotEnded
+        );
+        require!(
+            !campaign.holds_deposit(),
+            PlaylinkAirdropErr::DepositNotWithdrawn
+        );
+        let campaign = ctx
+            .accounts
+            .airdrop_platform
+            .all_campaigns
+            .remove(campaign_index);
+
+        // The fee was earned, only the SOL left to airdrop goes back to the creator.
+        // Token assets never left the creator's account, or were withdrawn from the vault already.
+        let reclaimed_amount = sol_amount(&campaign.assets)?;
+        let airdrop_platform = ctx.accounts.airdrop_platform.to_account_info();
+        transfer_from_platform(
+            &airdrop_platform,
+            &ctx.accounts.campaign_creator.to_account_info(),
+            reclaimed_amount,
+        )?;
+
+        emit!(CampaignReclaimed {
+            campaign_id,
+            creator: campaign.creator,
+            reclaimed_amount
+        });
+
+        Ok(())
+    }
+
+    pub fn airdrop<'info>(
+        ctx: Context<'_, '_, '_, 'info, Airdrop<'info>>,
+        campaign_id: String,
+        asset_index: u64,
+    ) -> Result<()> {
+        // Find the corresponding campaign and asset
+        let (campaign_index, asset_index) = ctx.accounts.airdrop_platform.find_started_asset(
+            &campaign_id,
+            ctx.accounts.campaign_creator.key(),
+            asset_index,
+            AssetKind::Token {
+                mint: ctx.accounts.mint.key(),
+            },
+        )?;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            campaign.is_operator(
+                &ctx.accounts.airdrop_platform.operators,
+                ctx.accounts.operator.key()
+            ),
+            PlaylinkAirdropErr::NotCampaignOperator
+        );
+        campaign.check_creator_cosign(&ctx.accounts.campaign_creator)?;
+        campaign.check_source_ata(
+            &ctx.accounts.creator_ata,
+            ctx.accounts.airdrop_platform.key(),
+        )?;
+        campaign.check_holding(ctx.remaining_accounts, ctx.accounts.recipient.key())?;
+        let asset = campaign.assets[asset_index];
+        let freeze_on_airdrop = campaign.freeze_on_airdrop;
+        let deposited = campaign.deposited;
+
+        // The mint may have been denied since the campaign was created
+        require!(
+            !ctx.accounts
+                .airdrop_platform
+                .denied_mints
+                .contains(&ctx.accounts.mint.key()),
+            PlaylinkAirdropErr::MintDenied
+        );
+
+        // Freezing the airdropped tokens requires the platform to hold the mint freeze authority
+        require!(
+            !freeze_on_airdrop
+                || ctx.accounts.mint.freeze_authority
+                    == COption::Some(ctx.accounts.airdrop_platform.key()),
+            PlaylinkAirdropErr::MissingFreezeAuthority
+        );
+
+        // Keep track of what this recipient received from the campaign
+        ctx.accounts.receipt.record(
+            &[(asset_index, asset.available_amount)],
+            campaign,
+            *ctx.bumps.get("receipt").unwrap(),
+        )?;
+        fit_account(
+            &ctx.accounts.receipt,
+            &ctx.accounts.operator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        // A failed CPI aborts the whole transaction before its result reaches us,
+        // so check the creator's balance upfront to surface a meaningful error
+        require!(
+            ctx.accounts.creator_ata.amount >= asset.available_amount,
+            PlaylinkAirdropErr::InsufficientCreatorBalance
+        );
+
+        // Update status before any CPI, completing the campaign if all assets are airdropped.
+        // Should a CPI fail, the whole instruction fails and none of this is committed.
+        let total_distributed = ctx.accounts.airdrop_platform.consume_asset(
+            campaign_index,
+            asset_index,
+            asset.available_amount,
+        )?;
+        ctx.accounts
+            .airdrop_platform
+            .record_activity(ctx.accounts.operator.key())?;
+
+        // Fresh wallets have no ATA yet, create it at the operator's expense
+        if ctx.accounts.recipient_ata.data_is_empty() {
+            associated_token::create(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: ctx.accounts.operator.to_account_info(),
+                    associated_token: ctx.accounts.recipient_ata.to_account_info(),
+                    authority: ctx.accounts.recipient.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+            ))?;
+        }
+
+        // With a percentage fee, its cut goes to the platform fee vault, passed as the first remaining account.
+        // Deposited tokens already sit in that vault, where the cut stays. Either way, it is recorded.
+        let fee_amount = ctx
+            .accounts
+            .airdrop_platform
+            .take_fee_cut(ctx.accounts.mint.key(), asset.available_amount)?;
+        if fee_amount > 0 && !deposited {
+            let fee_vault = ctx
+                .remaining_accounts
+                .first()
+                .ok_or(PlaylinkAirdropErr::InvalidFeeVault)?;
+            check_fee_vault(
+                fee_vault,
+                ctx.accounts.airdrop_platform.key(),
+                ctx.accounts.mint.key(),
+            )?;
+            transfer_asset(
+                &ctx.accounts.creator_ata.to_account_info(),
+                &ctx.accounts.mint.to_account_info(),
+                fee_vault,
+                &ctx.accounts.airdrop_platform,
+                fee_amount,
+                asset.decimals,
+            )?;
+        }
+
+        // Airdrop - PDA signs by seeds and bump
+        transfer_asset(
+            &ctx.accounts.creator_ata.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.recipient_ata.to_account_info(),
+            &ctx.accounts.airdrop_platform,
+            asset.available_amount - fee_amount,
+            asset.decimals,
+        )?;
+
+        // Compliance campaigns keep the tokens frozen until the recipient is cleared
+        if freeze_on_airdrop {
+            set_account_frozen(
+                &ctx.accounts.recipient_ata.to_account_info(),
+                &ctx.accounts.mint.to_account_info(),
+                &ctx.accounts.airdrop_platform,
+                true,
+            )?;
+        }
+
+        emit!(AirdropExecuted {
+            campaign_id: campaign_id.clone(),
+            asset_index: asset_index as u64,
+            recipient: ctx.accounts.recipient.key(),
+            amount: asset.available_amount,
+            total_distributed
+        });
+
+        // Same airdrop in the common schema ingested by off-the-shelf indexers
+        emit!(StandardAirdropEvent {
+            program_id: ID,
+            campaign: campaign_id,
+            recipient: ctx.accounts.recipient.key(),
+            mint: ctx.accounts.mint.key(),
+            amount: asset.available_amount - fee_amount,
+            timestamp: current_timestamp()?
+        });
+
+        Ok(())
+    }
+
+    pub fn airdrop_by_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, Airdrop<'info>>,
+        campaign_id: String,
+    ) -> Result<()> {
+        // Locate the asset by its mint, which does not change when an update reorders the assets.
+        // With several assets of the same mint, the first one still available is airdropped.
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_started_campaign(&campaign_id, ctx.accounts.campaign_creator.key())?;
+        let kind = AssetKind::Token {
+            mint: ctx.accounts.mint.key(),
+        };
+        let assets = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index].assets;
+        let asset_index = assets
+            .iter()
+            .position(|asset| asset.kind == kind && asset.available_amount > 0)
+            .or_else(|| assets.iter().position(|asset| asset.kind == kind))
+            .ok_or(PlaylinkAirdropErr::AssetAddressMismatch)?;
+        airdrop(ctx, campaign_id, asset_index as u64)
+    }
+
+    pub fn airdrop_all_assets<'info>(
+        ctx: Context<'_, '_, '_, 'info, AirdropAllAssets<'info>>,
+        campaign_id: String,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        // Oversized account lists would only fail later with a cryptic runtime error
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            PlaylinkAirdropErr::BatchTooLarge
+        );
+
+        // One amount per asset of the campaign, zero skips an asset
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_started_campaign(&campaign_id, ctx.accounts.campaign_creator.key())?;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            campaign.is_operator(
+                &ctx.accounts.airdrop_platform.operators,
+                ctx.accounts.operator.key()
+            ),
+            PlaylinkAirdropErr::NotCampaignOperator
+        );
+        campaign.check_creator_cosign(&ctx.accounts.campaign_creator)?;
+        campaign.check_holding(ctx.remaining_accounts, ctx.accounts.recipient.key())?;
+        require!(!campaign.deposited, PlaylinkAirdropErr::CampaignDeposited);
+        require!(
+            amounts.len() == campaign.assets.len(),
+            PlaylinkAirdropErr::LengthsMismatch
+        );
+
+        // Every token asset comes with the creator ATA, the recipient ATA and the mint,
+        // followed by the platform fee vault of the mint with a percentage fee.
+        // They are checked the same way as for a single airdrop.
+        let platform_key = ctx.accounts.airdrop_platform.key();
+        let recipient = ctx.accounts.recipient.key();
+        let with_fee_vault = ctx.accounts.airdrop_platform.fee_bps > 0;
+        let chunk_len = if with_fee_vault { 4 } else { 3 };
+        let mut token_accounts = ctx.remaining_accounts.chunks(chunk_len);
+        let mut transfers = Vec::new();
+        let mut claims = Vec::new();
+        for (asset_index, (asset, &amount)) in campaign.assets.iter().zip(&amounts).enumerate() {
+            let accounts = match asset.kind {
+                AssetKind::Token { .. } => Some(
+                    token_accounts
+                        .next()
+                        .filter(|accounts| accounts.len() == chunk_len)
+                        .ok_or(PlaylinkAirdropErr::LengthsMismatch)?,
+                ),
+                AssetKind::Sol => None,
+            };
+            if amount == 0 {
+                continue;
+            }
+            require!(
+                amount <= asset.available_amount && (!asset.is_nft || amount == 1),
+                PlaylinkAirdropErr::InvalidClaimAmount
+            );
+            if let (AssetKind::Token { mint }, Some(accounts)) = (asset.kind, accounts) {
+                let creator_ata = Account::<TokenAccount>::try_from(&accounts[0])?;
+                let recipient_ata = Account::<TokenAccount>::try_from(&accounts[1])?;
+                let mint_account = Account::<Mint>::try_from(&accounts[2])?;
+                require!(
+                    mint_account.key() == mint,
+                    PlaylinkAirdropErr::AssetAddressMismatch
+                );
+                require!(
+                    !ctx.accounts.airdrop_platform.denied_mints.contains(&mint),
+                    PlaylinkAirdropErr::MintDenied
+                );
+                require!(
+                    creator_ata.owner == campaign.creator && creator_ata.mint == mint,
+                    PlaylinkAirdropErr::InvalidCreatorAta
+                );
+                require!(
+                    recipient_ata.key() != creator_ata.key(),
+                    PlaylinkAirdropErr::SelfAirdrop
+                );
+                require!(
+                    recipient_ata.key() == get_associated_token_address(&recipient, &mint),
+                    PlaylinkAirdropErr::InvalidRecipientAta
+                );
+                require!(
+                    creator_ata.amount >= amount,
+                    PlaylinkAirdropErr::InsufficientCreatorBalance
+                );
+                require!(
+                    !campaign.freeze_on_airdrop
+                        || mint_account.freeze_authority == COption::Some(platform_key),
+                    PlaylinkAirdropErr::MissingFreezeAuthority
+                );
+                if with_fee_vault {
+                    check_fee_vault(&accounts[3], platform_key, mint)?;
+                }
+            }
+            transfers.push((asset_index, *asset, amount, accounts));
+            claims.push((asset_index, amount));
+        }
+        let freeze_on_airdrop = campaign.freeze_on_airdrop;
+
+        // Keep track of what this recipient received from the campaign
+        ctx.accounts
+            .receipt
+            .record(&claims, campaign, *ctx.bumps.get("receipt").unwrap())?;
+        fit_account(
+            &ctx.accounts.receipt,
+            &ctx.accounts.operator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        // Update status before any transfer. The campaign can only be drained by the last
+        // non-zero amount, so it is still in place for every asset before it.
+        let mut total_distributed = Vec::with_capacity(transfers.len());
+        for &(asset_index, _, amount, _) in transfers.iter() {
+            total_distributed.push(ctx.accounts.airdrop_platform.consume_asset(
+                campaign_index,
+                asset_index,
+                amount,
+            )?);
+        }
+        ctx.accounts
+            .airdrop_platform
+            .record_activity(ctx.accounts.operator.key())?;
+
+        // Airdrop tokens by PDA delegation first, so that the SOL from the platform deposit is
+        // only moved by hand once every CPI has succeeded
+        for &(_, asset, amount, accounts) in transfers.iter() {
+            if let (AssetKind::Token { mint }, Some(accounts)) = (asset.kind, accounts) {
+                let fee_amount = ctx.accounts.airdrop_platform.take_fee_cut(mint, amount)?;
+                if fee_amount > 0 {
+                    transfer_asset(
+                        &accounts[0],
+                        &accounts[2],
+                        &accounts[3],
+                        &ctx.accounts.airdrop_platform,
+                        fee_amount,
+                        asset.decimals,
+                    )?;
+                }
+                transfer_asset(
+                    &accounts[0],
+                    &accounts[2],
+                    &accounts[1],
+                    &ctx.accounts.airdrop_platform,
+                    amount - fee_amount,
+                    asset.decimals,
+                )?;
+                if freeze_on_airdrop {
+                    set_account_frozen(
+                        &accounts[1],
+                        &accounts[2],
+                        &ctx.accounts.airdrop_platform,
+                        true,
+                    )?;
+                }
+            }
+        }
+        for ((asset_index, _, amount, accounts), total_distributed) in
+            transfers.into_iter().zip(total_distributed)
+        {
+            if accounts.is_none() {
+                transfer_from_platform(
+                    &ctx.accounts.airdrop_platform.to_account_info(),
+                    &ctx.accounts.recipient.to_account_info(),
+                    amount,
+                )?;
+            }
+
+            emit!(AirdropExecuted {
+                campaign_id: campaign_id.clone(),
+                asset_index: asset_index as u64,
+                recipient,
+                amount,
+                total_distributed
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn batch_airdrop<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchAirdrop<'info>>,
+        campaign_id: String,
+        asset_index: u64,
+        start_index: u64,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            PlaylinkAirdropErr::BatchTooLarge
+        );
+        let (campaign_index, asset_index) =
+            find_batch_asset(ctx.accounts, &campaign_id, asset_index)?;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        let asset = campaign.assets[asset_index];
+
+        // An interrupted batch resumes exactly where the previous one stopped
+        require!(
+            start_index == campaign.next_index,
+            PlaylinkAirdropErr::BatchOutOfOrder
+        );
+
+        // One existing ATA per recipient follows the fee vault, if any
+        let (fee_vault, recipient_atas) = split_fee_vault(ctx.remaining_accounts, ctx.accounts)?;
+        require!(
+            !amounts.is_empty() && amounts.len() == recipient_atas.len(),
+            PlaylinkAirdropErr::LengthsMismatch
+        );
+        for (recipient_ata, &amount) in recipient_atas.iter().zip(&amounts) {
+            validate_batch_recipient_ata(recipient_ata, ctx.accounts)?;
+            require!(
+                amount > 0 && (!asset.is_nft || amount == 1),
+                PlaylinkAirdropErr::InvalidClaimAmount
+            );
+        }
+        let total_amount = amounts
+            .iter()
+            .try_fold(0u64, |total, &amount| total.checked_add(amount))
+            .filter(|&total| total <= asset.available_amount)
+            .ok_or(PlaylinkAirdropErr::InvalidClaimAmount)?;
+        require!(
+            ctx.accounts.creator_ata.amount >= total_amount,
+            PlaylinkAirdropErr::InsufficientCreatorBalance
+        );
+
+        // Advance the batch progress before the campaign may be drained and removed
+        let campaign = &mut ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        let freeze_on_airdrop = campaign.freeze_on_airdrop;
+        campaign.next_index += amounts.len() as u64;
+        campaign.distributed_count += amounts.len() as u64;
+        let total_distributed = ctx.accounts.airdrop_platform.consume_asset(
+            campaign_index,
+            asset_index,
+            total_amount,
+        )?;
+        ctx.accounts
+            .airdrop_platform
+            .record_activity(ctx.accounts.operator.key())?;
+
+        // Compute usage helps sizing the batches, but is only logged on demand
+        let debug_logging = ctx.accounts.airdrop_platform.debug_logging;
+        if debug_logging {
+            sol_log_compute_units();
+        }
+        transfer_shares(
+            ctx.accounts,
+            fee_vault,
+            recipient_atas,
+            &amounts,
+            asset.decimals,
+            freeze_on_airdrop,
+        )?;
+        if debug_logging {
+            msg!("Batch airdrop: {} recipients processed", amounts.len());
+            sol_log_compute_units();
+        }
+
+        emit!(BatchAirdropExecuted {
+            campaign_id: campaign_id.clone(),
+            asset_index: asset_index as u64,
+            start_index,
+            recipients: amounts.len() as u64,
+            amount: total_amount,
+            total_distributed
+        });
+
+        // A single summary once every transfer of the batch went through, for indexers
+        // that only follow completed batches
+        emit!(BatchAirdropCompleted {
+            campaign_id,
+            recipient_count: amounts.len() as u64,
+            total_amount
+        });
+
+        Ok(())
+    }
+
+    pub fn airdrop_weighted<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchAirdrop<'info>>,
+        campaign_id: String,
+        asset_index: u64,
+        recipients: Vec<Pubkey>,
+        weights: Vec<u64>,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            PlaylinkAirdropErr::BatchTooLarge
+        );
+        let (campaign_index, asset_index) =
+            find_batch_asset(ctx.accounts, &campaign_id, asset_index)?;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        let asset = campaign.assets[asset_index];
+        let freeze_on_airdrop = campaign.freeze_on_airdrop;
+
+        // Every recipient comes with a weight and its existing ATA, after the fee vault if any
+        let (fee_vault, recipient_atas) = split_fee_vault(ctx.remaining_accounts, ctx.accounts)?;
+        require!(
+            !recipients.is_empty()
+                && recipients.len() == weights.len()
+                && recipients.len() == recipient_atas.len(),
+            PlaylinkAirdropErr::LengthsMismatch
+        );
+        for (recipient_ata, recipient) in recipient_atas.iter().zip(&recipients) {
+            require!(
+                recipient_ata.key()
+                    == get_associated_token_address(recipient, &ctx.accounts.mint.key()),
+                PlaylinkAirdropErr::InvalidRecipientAta
+            );
+            validate_batch_recipient_ata(recipient_ata, ctx.accounts)?;
+        }
+        require!(!asset.is_nft, PlaylinkAirdropErr::InvalidNftAsset);
+        require!(
+            ctx.accounts.creator_ata.amount >= asset.available_amount,
+            PlaylinkAirdropErr::InsufficientCreatorBalance
+        );
+
+        // Split the whole available amount by weight, the rounding remainder going to the last recipient
+        let total_weight = weights
+            .iter()
+            .try_fold(0u64, |total, &weight| total.checked_add(weight))
+            .filter(|&total| total > 0)
+            .ok_or(PlaylinkAirdropErr::InvalidWeights)?;
+        let mut shares: Vec<u64> = weights
+            .iter()
+            .map(|&weight| {
+                (asset.available_amount as u128 * weight as u128 / total_weight as u128) as u64
+            })
+            .collect();
+        let remainder = asset.available_amount - shares.iter().sum::<u64>();
+        if let Some(last_share) = shares.last_mut() {
+            *last_share += remainder;
+        }
+
+        // Token leftovers in the other assets too small to be worth airdropping are swept,
+        // so that the campaign completes once this asset is drained
+        let swept = ctx
+            .accounts
+            .airdrop_platform
+            .sweep_dust(campaign_index, asset_index);
+        if swept.iter().any(|&amount| amount > 0) {
+            emit!(DustSwept {
+                campaign_id: campaign_id.clone(),
+                amounts: swept
+            });
+        }
+
+        // Update status before any transfer, the asset is drained at once
+        let total_distributed = ctx.accounts.airdrop_platform.consume_asset(
+            campaign_index,
+            asset_index,
+            asset.available_amount,
+        )?;
+        ctx.accounts
+            .airdrop_platform
+            .record_activity(ctx.accounts.operator.key())?;
+
+        transfer_shares(
+            ctx.accounts,
+            fee_vault,
+            recipient_atas,
+            &shares,
+            asset.decimals,
+            freeze_on_airdrop,
+        )?;
+
+        for (recipient, amount) in recipients.into_iter().zip(shares) {
+            emit!(AirdropExecuted {
+                campaign_id: campaign_id.clone(),
+                asset_index: asset_index as u64,
+                recipient,
+                amount,
+                total_distributed
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn upload_recipients(
+        ctx: Context<UploadRecipients>,
+        campaign_id: String,
+        recipients: Vec<RecipientAmount>,
+        chunk_index: u32,
+    ) -> Result<()> {
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(ctx.accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        require!(
+            ctx.accounts.airdrop_platform.all_campaigns[campaign_index].is_operator(
+                &ctx.accounts.airdrop_platform.operators,
+                ctx.accounts.operator.key()
+            ),
+            PlaylinkAirdropErr::NotCampaignOperator
+        );
+
+        // Chunks are appended in order, each small enough to be distributed in one transaction
+        let recipient_list = &mut ctx.accounts.recipient_list;
+        require!(
+            chunk_index as usize == recipient_list.chunks.len(),
+            PlaylinkAirdropErr::BatchOutOfOrder
+        );
+        require!(
+            !recipients.is_empty()
+                && recipients.len() <= MAX_BATCH_SIZE
+                && recipient_list.chunks.len() < MAX_RECIPIENT_CHUNKS,
+            PlaylinkAirdropErr::BatchTooLarge
+        );
+        recipient_list.bump = *ctx.bumps.get("recipient_list").unwrap();
+        recipient_list.chunks.push(recipients.clone());
+
+        emit!(RecipientsUploaded {
+            campaign_id,
+            chunk_index,
+            recipients: recipients.len() as u64
+        });
+
+        Ok(())
+    }
+
+    pub fn distribute_chunk<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeChunk<'info>>,
+        campaign_id: String,
+        chunk_index: u32,
+    ) -> Result<()> {
+        // The chunk airdrops the campaign asset of the given mint
+        let accounts = &ctx.accounts.batch;
+        let campaign_index = accounts
+            .airdrop_platform
+            .find_campaign(accounts.campaign_creator.key(), &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        let mint_kind = AssetKind::Token {
+            mint: accounts.mint.key(),
+        };
+        let asset_index = accounts.airdrop_platform.all_campaigns[campaign_index]
+            .assets
+            .iter()
+            .position(|asset| asset.kind == mint_kind)
+            .ok_or(PlaylinkAirdropErr::AssetAddressMismatch)?;
+        let (campaign_index, asset_index) =
+            find_batch_asset(accounts, &campaign_id, asset_index as u64)?;
+        let asset = accounts.airdrop_platform.all_campaigns[campaign_index].assets[asset_index];
+        let freeze_on_airdrop =
+            accounts.airdrop_platform.all_campaigns[campaign_index].freeze_on_airdrop;
+
+        // Chunks are distributed once each, in the order they were uploaded
+        let recipient_list = &ctx.accounts.recipient_list;
+        require!(
+            chunk_index == recipient_list.next_chunk
+                && (chunk_index as usize) < recipient_list.chunks.len(),
+            PlaylinkAirdropErr::BatchOutOfOrder
+        );
+        let chunk = &recipient_list.chunks[chunk_index as usize];
+
+        // One existing ATA per recipient of the chunk follows the fee vault, if any
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE + 1,
+            PlaylinkAirdropErr::BatchTooLarge
+        );
+        let (fee_vault, recipient_atas) = split_fee_vault(ctx.remaining_accounts, accounts)?;
+        require!(
+            chunk.len() == recipient_atas.len(),
+            PlaylinkAirdropErr::LengthsMismatch
+        );
+        for (recipient_ata, recipient) in recipient_atas.iter().zip(chunk) {
+            require!(
+                recipient_ata.key()
+                    == get_associated_token_address(&recipient.recipient, &accounts.mint.key()),
+                PlaylinkAirdropErr::InvalidRecipientAta
+            );
+            validate_batch_recipient_ata(recipient_ata, accounts)?;
+            require!(
+                recipient.amount > 0 && (!asset.is_nft || recipient.amount == 1),
+                PlaylinkAirdropErr::InvalidClaimAmount
+            );
+        }
+        let amounts: Vec<u64> = chunk.iter().map(|recipient| recipient.amount).collect();
+        let total_amount = amounts
+            .iter()
+            .try_fold(0u64, |total, &amount| total.checked_add(amount))
+            .filter(|&total| total <= asset.available_amount)
+            .ok_or(PlaylinkAirdropErr::InvalidClaimAmount)?;
+        require!(
+            accounts.creator_ata.amount >= total_amount,
+            PlaylinkAirdropErr::InsufficientCreatorBalance
+        );
+
+        // Update status before any transfer
+        ctx.accounts.recipient_list.next_chunk += 1;
+        let airdrop_platform = &mut ctx.accounts.batch.airdrop_platform;
+        airdrop_platform.all_campaigns[campaign_index].distributed_count += amounts.len() as u64;
+        let total_distributed =
+            airdrop_platform.consume_asset(campaign_index, asset_index, total_amount)?;
+        airdrop_platform.record_activity(ctx.accounts.batch.operator.key())?;
+
+        transfer_shares(
+            &mut ctx.accounts.batch,
+            fee_vault,
+            recipient_atas,
+            &amounts,
+            asset.decimals,
+            freeze_on_airdrop,
+        )?;
+
+        emit!(RecipientChunkDistributed {
+            campaign_id,
+            chunk_index,
+            recipients: amounts.len() as u64,
+            amount: total_amount,
+            total_distributed
+        });
+
+        Ok(())
+    }
+
+    pub fn airdrop_sol(
+        ctx: Context<AirdropSol>,
+        campaign_id: String,
+        asset_index: u64,
+    ) -> Result<()> {
+        // Find the corresponding campaign and asset
+        let (campaign_index, asset_index) = ctx.accounts.airdrop_platform.find_started_asset(
+            &campaign_id,
+            ctx.accounts.campaign_creator.key(),
+            asset_index,
+            AssetKind::Sol,
+        )?;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            campaign.is_operator(
+                &ctx.accounts.airdrop_platform.operators,
+                ctx.accounts.operator.key()
+            ),
+            PlaylinkAirdropErr::NotCampaignOperator
+        );
+        campaign.check_creator_cosign(&ctx.accounts.campaign_creator)?;
+        campaign.check_holding(ctx.remaining_accounts, ctx.accounts.recipient.key())?;
+        let asset = campaign.assets[asset_index];
+
+        // Keep track of what this recipient received from the campaign
+        ctx.accounts.receipt.record(
+            &[(asset_index, asset.available_amount)],
+            campaign,
+            *ctx.bumps.get("receipt").unwrap(),
+        )?;
+        fit_account(
+            &ctx.accounts.receipt,
+            &ctx.accounts.operator.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        // Update status before the transfer, completing the campaign if all assets are airdropped
+        let total_distributed = ctx.accounts.airdrop_platform.consume_asset(
+            campaign_index,
+            asset_index,
+            asset.available_amount,
+        )?;
+        ctx.accounts
+            .airdrop_platform
+            .record_activity(ctx.accounts.operator.key())?;
+
+        // Airdrop - the SOL was deposited to the platform PDA when the campaign was funded
+        transfer_from_platform(
+            &ctx.accounts.airdrop_platform.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            asset.available_amount,
+        )?;
+
+        emit!(AirdropExecuted {
+            campaign_id,
+            asset_index: asset_index as u64,
+            recipient: ctx.accounts.recipient.key(),
+            amount: asset.available_amount,
+            total_distributed
+        });
+
+        Ok(())
+    }
+
+    pub fn simulate_airdrop(
+        ctx: Context<SimulateAirdrop>,
+        campaign_id: String,
+        asset_index: u64,
+        amount: u64,
+    ) -> Result<()> {
+        // Same checks as an airdrop, without moving tokens or updating any state
+        let (campaign_index, asset_index) = ctx.accounts.airdrop_platform.find_started_asset(
+            &campaign_id,
+            ctx.accounts.campaign_creator.key(),
+            asset_index,
+            AssetKind::Token {
+                mint: ctx.accounts.mint.key(),
+            },
+        )?;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        let asset = campaign.assets[asset_index];
+        require!(
+            !ctx.accounts
+                .airdrop_platform
+                .denied_mints
+                .contains(&ctx.accounts.mint.key()),
+            PlaylinkAirdropErr::MintDenied
+        );
+        require!(
+            amount <= asset.available_amount && (!asset.is_nft || amount == 1),
+            PlaylinkAirdropErr::InvalidClaimAmount
+        );
+
+        // The recipient must not have received its share already
+        let mut receipt = if ctx.accounts.receipt.data_is_empty() {
+            RecipientReceipt::default()
+        } else {
+            (*Account::<RecipientReceipt>::try_from(&ctx.accounts.receipt)?).clone()
+        };
+        receipt.record(&[(asset_index, amount)], campaign, 0)?;
+
+        // The creator must hold and delegate enough tokens, and the recipient ATA must exist
+        let creator_ata = &ctx.accounts.creator_ata;
+        require!(
+            creator_ata.amount >= amount,
+            PlaylinkAirdropErr::InsufficientCreatorBalance
+        );
+        require!(
+            creator_ata.delegate == COption::Some(ctx.accounts.airdrop_platform.key())
+                && creator_ata.delegated_amount >= amount,
+            PlaylinkAirdropErr::InsufficientDelegation
+        );
+        require!(
+            !ctx.accounts.recipient_ata.data_is_empty(),
+            PlaylinkAirdropErr::InvalidRecipientAta
+        );
+
+        Ok(())
+    }
+
+    pub fn claim_with_authorization<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimWithAuthorization<'info>>,
+        campaign_id: String,
+        asset_index: u64,
+        amount: u64,
+        expiry: u64,
+        signature: [u8; 64],
+    ) -> Result<()> {
+        // Reject stale and already used authorizations
+        require!(
+            (current_timestamp()? as u64) <= expiry,
+            PlaylinkAirdropErr::AuthorizationExpired
+        );
+        let receipt = &mut ctx.accounts.receipt;
+        require!(
+            expiry > receipt.last_authorization_expiry,
+            PlaylinkAirdropErr::AuthorizationAlreadyUsed
+        );
+
+        // The operator signature is verified by the Ed25519 program in the previous instruction,
+        // and only holds for this program and platform
+        let message = [
+            crate::ID.as_ref(),
+            ctx.accounts.airdrop_platform.key().as_ref(),
+            ctx.accounts.campaign_creator.key().as_ref(),
+            campaign_id.as_bytes(),
+            &asset_index.to_le_bytes(),
+            ctx.accounts.recipient.key().as_ref(),
+            &amount.to_le_bytes(),
+            &expiry.to_le_bytes(),
+        ]
+        .concat();
+        let operator = verify_ed25519_instruction(
+            &ctx.accounts.instructions.to_account_info(),
+            &message,
+            &signature,
+        )?;
+        require!(
+            ctx.accounts
+                .airdrop_platform
+                .operators
+                .iter()
+                .any(|op| op.key() == operator),
+            PlaylinkAirdropErr::InvalidAuthorization
+        );
+
+        // Find the corresponding campaign and asset
+        let (campaign_index, asset_index) = ctx.accounts.airdrop_platform.find_started_asset(
+            &campaign_id,
+            ctx.accounts.campaign_creator.key(),
+            asset_index,
+            AssetKind::Token {
+                mint: ctx.accounts.mint.key(),
+            },
+        )?;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        let asset = campaign.assets[asset_index];
+        require!(
+            !ctx.accounts
+                .airdrop_platform
+                .denied_mints
+                .contains(&ctx.accounts.mint.key()),
+            PlaylinkAirdropErr::MintDenied
+        );
+        require!(
+            amount <= asset.available_amount && (!asset.is_nft || amount == 1),
+            PlaylinkAirdropErr::InvalidClaimAmount
+        );
+        require!(!campaign.deposited, PlaylinkAirdropErr::CampaignDeposited);
+        campaign.check_holding(ctx.remaining_accounts, ctx.accounts.recipient.key())?;
+
+        // Recipients lose eligibility once the claim window after the start has passed
+        require!(
+            campaign.claim_window == 0
+                || current_timestamp()? as u64 <= campaign.starting_time + campaign.claim_window,
+            PlaylinkAirdropErr::ClaimWindowClosed
+        );
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.record(
+            &[(asset_index, amount)],
+            campaign,
+            *ctx.bumps.get("receipt").unwrap(),
+        )?;
+        receipt.last_authorization_expiry = expiry;
+        fit_account(
+            &ctx.accounts.receipt,
+            &ctx.accounts.recipient.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        require!(
+            ctx.accounts.creator_ata.amount >= amount,
+            PlaylinkAirdropErr::InsufficientCreatorBalance
+        );
+
+        // Recipients pay the platform claim fee themselves, zero means free claims
+        let claim_fee = ctx.accounts.airdrop_platform.claim_fee;
+        ctx.accounts.airdrop_platform.accumulated_fees = ctx
+            .accounts
+            .airdrop_platform
+            .accumulated_fees
+            .checked_add(claim_fee)
+            .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?;
+        transfer_to_platform(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            &ctx.accounts.airdrop_platform.to_account_info(),
+            claim_fee,
+        )?;
+
+        // Update status before the transfer
+        ctx.accounts
+            .airdrop_platform
+            .consume_asset(campaign_index, asset_index, amount)?;
+
+        // With a percentage fee, its cut goes to the platform fee vault, passed as the first remaining account
+        let fee_amount = ctx
+            .accounts
+            .airdrop_platform
+            .take_fee_cut(ctx.accounts.mint.key(), amount)?;
+        if fee_amount > 0 {
+            let fee_vault = ctx
+                .remaining_accounts
+                .first()
+                .ok_or(PlaylinkAirdropErr::InvalidFeeVault)?;
+            check_fee_vault(
+                fee_vault,
+                ctx.accounts.airdrop_platform.key(),
+                ctx.accounts.mint.key(),
+            )?;
+            transfer_asset(
+                &ctx.accounts.creator_ata.to_account_info(),
+                &ctx.accounts.mint.to_account_info(),
+                fee_vault,
+                &ctx.accounts.airdrop_platform,
+                fee_amount,
+                asset.decimals,
+            )?;
+        }
+
+        // Transfer - PDA signs by seeds and bump
+        transfer_asset(
+            &ctx.accounts.creator_ata.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.recipient_ata.to_account_info(),
+            &ctx.accounts.airdrop_platform,
+            amount - fee_amount,
+            asset.decimals,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_airdrop_fee(ctx: Context<WithdrawAirdropFee>) -> Result<()> {
+        // With a withdrawal delay, fees can only be withdrawn through a timelocked request
+        require!(
+            ctx.accounts.airdrop_platform.withdrawal_delay == 0,
+            PlaylinkAirdropErr::WithdrawalTimelocked
+        );
+
+        // Only collected fees are withdrawn, whatever else the platform holds,
+        // and never the fees that creators may still get back
+        let amount = ctx
+            .accounts
+            .airdrop_platform
+            .withdrawable_fees(current_timestamp()?)?;
+        ctx.accounts.airdrop_platform.accumulated_fees -= amount;
+        ctx.accounts
+            .airdrop_platform
+            .record_withdrawal(ctx.accounts.recipient.key(), amount)?;
+        transfer_from_platform(
+            &ctx.accounts.airdrop_platform.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            amount,
+        )
+    }
+
+    pub fn request_withdrawal(ctx: Context<RequestWithdrawal>, amount: u64) -> Result<()> {
+        // One request at a time, for fees that were actually collected and are not reserved for refunds
+        let airdrop_platform = &mut ctx.accounts.airdrop_platform;
+        require!(
+            airdrop_platform.pending_withdrawal == 0,
+            PlaylinkAirdropErr::WithdrawalPending
+        );
+        require!(
+            amount > 0 && amount <= airdrop_platform.withdrawable_fees(current_timestamp()?)?,
+            PlaylinkAirdropErr::InvalidWithdrawalAmount
+        );
+        airdrop_platform.pending_withdrawal = amount;
+        airdrop_platform.withdrawal_requested_at = current_timestamp()?;
+        Ok(())
+    }
+
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+        // The request must have waited for the whole delay
+        let airdrop_platform = &mut ctx.accounts.airdrop_platform;
+        require!(
+            airdrop_platform.pending_withdrawal > 0,
+            PlaylinkAirdropErr::NoPendingWithdrawal
+        );
+        require!(
+            current_timestamp()?
+                >= airdrop_platform.withdrawal_requested_at
+                    + airdrop_platform.withdrawal_delay as i64,
+            PlaylinkAirdropErr::WithdrawalTimelocked
+        );
+
+        // Fees may have been refunded or reserved since the request, never withdraw more than what is left
+        let amount = airdrop_platform
+            .pending_withdrawal
+            .min(airdrop_platform.withdrawable_fees(current_timestamp()?)?);
+        airdrop_platform.accumulated_fees -= amount;
+        airdrop_platform.pending_withdrawal = 0;
+        airdrop_platform.withdrawal_requested_at = 0;
+        airdrop_platform.record_withdrawal(ctx.accounts.recipient.key(), amount)?;
+        transfer_from_platform(
+            &ctx.accounts.airdrop_platform.to_account_info(),
+            &ctx.accounts.recipient.to_account_info(),
+            amount,
+        )
+    }
+
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>) -> Result<()> {
+        let airdrop_platform = &mut ctx.accounts.airdrop_platform;
+        require!(
+            airdrop_platform.pending_withdrawal > 0,
+            PlaylinkAirdropErr::NoPendingWithdrawal
+        );
+        airdrop_platform.pending_withdrawal = 0;
+        airdrop_platform.withdrawal_requested_at = 0;
+        Ok(())
+    }
+
+    pub fn admin_sweep_tokens(ctx: Context<AdminSweepTokens>, amount: u64) -> Result<()> {
+        // Only token accounts owned by the platform itself can be swept, campaign assets
+        // stay in the creators' ATAs and are merely delegated to the platform.
+        // Tokens deposited in a vault are not the platform's to sweep though.
+        // Sweeping a vault takes the percentage fees collected in it first.
+        let mint = ctx.accounts.mint.key();
+        if ctx.accounts.source.key()
+            == get_associated_token_address(&ctx.accounts.airdrop_platform.key(), &mint)
+        {
+            let deposited_amount = ctx.accounts.airdrop_platform.deposited_amount(mint);
+            require!(
+                ctx.accounts.source.amount.saturating_sub(amount) >= deposited_amount,
+                PlaylinkAirdropErr::InsufficientPlatformBalance
+            );
+            if let Some(fee) = ctx
+                .accounts
+                .airdrop_platform
+                .token_fees
+                .iter_mut()
+                .find(|fee| fee.mint == mint)
+            {
+                fee.amount = fee.amount.saturating_sub(amount);
+            }
+        }
+        transfer_asset(
+            &ctx.accounts.source.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.destination.to_account_info(),
+            &ctx.accounts.airdrop_platform,
+            amount,
+            ctx.accounts.mint.decimals,
+        )
+    }
+
+    pub fn withdrawable_fees(ctx: Context<WithdrawableFees>) -> Result<u64> {
+        ctx.accounts
+            .airdrop_platform
+            .withdrawable_fees(current_timestamp()?)
+    }
+
+    pub fn get_asset_amounts(
+        ctx: Context<GetAssetAmounts>,
+        campaign_id: String,
+        creator: Pubkey,
+    ) -> Result<Vec<u64>> {
+        // Remaining amount of every asset, in the same order as the campaign assets
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(creator, &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        Ok(ctx.accounts.airdrop_platform.all_campaigns[campaign_index]
+            .assets
+            .iter()
+            .map(|asset| asset.available_amount)
+            .collect())
+    }
+
+    pub fn claimable_amount(
+        ctx: Context<ClaimableAmount>,
+        campaign_id: String,
+        creator: Pubkey,
+        asset_index: u64,
+        _recipient: Pubkey,
+    ) -> Result<u64> {
+        // What the recipient could claim from an asset right now, given its vesting schedule
+        let campaign_index = ctx
+            .accounts
+            .airdrop_platform
+            .find_campaign(creator, &campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        let campaign = &ctx.accounts.airdrop_platform.all_campaigns[campaign_index];
+        require!(
+            (asset_index as usize) < campaign.assets.len(),
+            PlaylinkAirdropErr::InvalidAssetIndex
+        );
+
+        // Nothing claimed yet if the recipient has no receipt
+        let claimed = if ctx.accounts.receipt.data_is_empty() {
+            0
+        } else {
+            Account::<RecipientReceipt>::try_from(&ctx.accounts.receipt)?
+                .claimed(asset_index as usize)
+        };
+        let now = current_timestamp()? as u64;
+        Ok(campaign.claimable_amount(asset_index as usize, claimed, now))
+    }
+
+    pub fn get_creator_campaigns(
+        ctx: Context<GetCreatorCampaigns>,
+        creator: Pubkey,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<AirdropCampaign>> {
+        // Return a page of the creator's campaigns, in campaign id order.
+        // Return data is capped at 1024 bytes, so pages should only hold a few campaigns.
+        Ok(ctx
+            .accounts
+            .airdrop_platform
+            .all_campaigns
+            .iter()
+            .filter(|campaign| campaign.creator == creator)
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    pub fn list_campaign_ids(
+        ctx: Context<ListCampaignIds>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<String>> {
+        // Return a page of the ids of the campaigns not completed yet, in campaign order.
+        // Ids alone keep the return data small enough for larger pages.
+        Ok(ctx
+            .accounts
+            .airdrop_platform
+            .all_campaigns
+            .iter()
+            .filter(|campaign| !campaign.completed)
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|campaign| campaign.campaign_id.clone())
+            .collect())
+    }
+
+    pub fn has_claimed(
+        ctx: Context<HasClaimed>,
+        _campaign_id: String,
+        _creator: Pubkey,
+        _recipient: Pubkey,
+    ) -> Result<bool> {
+        // Receipts are only created when a recipient receives something
+        if ctx.accounts.receipt.data_is_empty() {
+            return Ok(false);
+        }
+        Ok(
+            Account::<RecipientReceipt>::try_from(&ctx.accounts.receipt)?
+                .claimed
+                .iter()
+                .any(|&claimed| claimed > 0),
+        )
+    }
+
+    pub fn get_recent_withdrawals(
+        ctx: Context<GetRecentWithdrawals>,
+    ) -> Result<Vec<FeeWithdrawal>> {
+        // Oldest first
+        Ok(ctx.accounts.airdrop_platform.recent_withdrawals.clone())
+    }
+
+    pub fn get_operator_activity(
+        ctx: Context<GetOperatorActivity>,
+    ) -> Result<Vec<OperatorActivity>> {
+        // Every operator along with when it last signed, zero if it never did
+        let airdrop_platform = &ctx.accounts.airdrop_platform;
+        Ok(airdrop_platform
+            .operators
+            .iter()
+            .map(|operator| OperatorActivity {
+                operator: *operator,
+                last_active: airdrop_platform
+                    .operator_activity
+                    .iter()
+                    .find(|activity| activity.operator == *operator)
+                    .map_or(0, |activity| activity.last_active),
+            })
+            .collect())
+    }
+
+    pub fn list_operators(
+        ctx: Context<ListOperators>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<Pubkey>> {
+        // Return a page of operators, clamped to the operator list bounds
+        let operators = &ctx.accounts.airdrop_platform.operators;
+        let start = (offset as usize).min(operators.len());
+        let end = start.saturating_add(limit as usize).min(operators.len());
+        Ok(operators[start..end].to_vec())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn init_platform(
+    airdrop_platform: &mut AirdropPlatform,
+    admin: Pubkey,
+    bump: u8,
+    platform_id: String,
+    fee_per_asset: u64,
+    min_fee_per_asset: u64,
+    max_fee_per_asset: u64,
+    max_total_campaigns: u32,
+) -> Result<()> {
+    require!(
+        min_fee_per_asset <= fee_per_asset && fee_per_asset <= max_fee_per_asset,
+        PlaylinkAirdropErr::FeeOutOfBounds
+    );
+
+    airdrop_platform.admin = admin;
+    airdrop_platform.fee_per_asset = fee_per_asset;
+    airdrop_platform.operators.push(admin);
+    airdrop_platform.bump = bump;
+    airdrop_platform.min_fee_per_asset = min_fee_per_asset;
+    airdrop_platform.max_fee_per_asset = max_fee_per_asset;
+    airdrop_platform.schema_version = SCHEMA_VERSION;
+    airdrop_platform.platform_id = platform_id;
+    airdrop_platform.max_total_campaigns = max_total_campaigns;
+
+    emit!(PlatformInitialized {
+        admin,
+        fee_per_asset
+    });
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_campaign(
+    airdrop_platform: &mut AirdropPlatform,
+    creator: Pubkey,
+    campaign_id: String,
+    assets: Vec<Asset>,
+    starting_time: u64,
+    ending_time: u64,
+    metadata_uri: Option<String>,
+    category: Option<String>,
+    max_per_recipient: u64,
+    airdrop_fee: u64,
+    fee_per_asset: u64,
+    fee_per_asset_per_day: u64,
+    mints: &[AccountInfo],
+) -> Result<()> {
+    // Number every campaign, generating its id from that number if none is given
+    airdrop_platform.campaign_counter += 1;
+    airdrop_platform.accumulated_fees += airdrop_fee;
+    let campaign_counter = airdrop_platform.campaign_counter;
+    let campaign_id = if campaign_id.is_empty() {
+        campaign_counter.to_string()
+    } else {
+        campaign_id
+    };
+    validate_campaign_id(&campaign_id)?;
+
+    // Bound the platform growth, zero means unlimited
+    require!(
+        airdrop_platform.max_total_campaigns == 0
+            || airdrop_platform.all_campaigns.len() < airdrop_platform.max_total_campaigns as usize,
+        PlaylinkAirdropErr::PlatformFull
+    );
+
+    // Check if campaign exists
+    let campaign_index = match airdrop_platform.find_campaign(creator, &campaign_id) {
+        Ok(_) => return err!(PlaylinkAirdropErr::CampaignAlreadyCreated),
+        Err(index) => index,
+    };
+
+    // Validate data
+    validate_starting_time(starting_time)?;
+    validate_ending_time(
+        starting_time,
+        ending_time,
+        airdrop_platform.min_campaign_duration,
+    )?;
+    validate_metadata_uri(&metadata_uri)?;
+    validate_category(&category)?;
+    validate_asset_mints(&assets, mints)?;
+    validate_mints_allowed(&assets, airdrop_platform)?;
+    let total_available_assets = total_amount(&assets)?;
+
+    // Create new airdrop campaign, keeping campaigns sorted by creator and id
+    airdrop_platform.all_campaigns.insert(
+        campaign_index,
+        AirdropCampaign {
+            campaign_id: campaign_id.clone(),
+            creator,
+            assets: assets.clone(),
+            starting_time,
+            ending_time,
+            total_available_assets,
+            airdrop_fee,
+            metadata_uri: metadata_uri.clone(),
+            category: category.clone(),
+            max_per_recipient,
+            total_distributed: 0,
+            paused: false,
+            completed: false,
+            recipient_cooldown: 0,
+            freeze_on_airdrop: false,
+            vesting_start: 0,
+            vesting_end: 0,
+            claim_window: 0,
+            campaign_operators: Vec::new(),
+            distributed_count: 0,
+            next_index: 0,
+            require_creator_cosign: false,
+            deposited: false,
+            min_holding_mint: None,
+            min_holding_amount: 0,
+            fee_per_asset,
+            allowed_distributors: Vec::new(),
+            fee_per_asset_per_day,
+            completed_at: 0,
+        },
+    );
+
+    emit!(AirdropCampaignCreated {
+        campaign_id,
+        creator,
+        assets,
+        starting_time,
+        ending_time,
+        metadata_uri,
+        category,
+        campaign_counter
+    });
+
+    Ok(())
+}
+
+/// Current time from the clock sysvar, failing cleanly if it cannot be read
+fn current_timestamp() -> Result<i64> {
+    Ok(clock::Clock::get()
+        .map_err(|_| PlaylinkAirdropErr::ClockUnavailable)?
+        .unix_timestamp)
+}
+
+/// Campaigns must start late enough to leave time for funding them,
+/// but not so far that the delegated assets are locked for good
+fn validate_starting_time(starting_time: u64) -> Result<()> {
+    let now = current_timestamp()? as u64;
+    require!(
+        starting_time >= now + MIN_START_DELAY,
+        PlaylinkAirdropErr::LowStartingTime
+    );
+    require!(
+        starting_time <= now + MAX_SCHEDULE_HORIZON,
+        PlaylinkAirdropErr::ScheduleTooFar
+    );
+    Ok(())
+}
+
+/// Campaigns end after they start, an ending time of zero leaves them open-ended
+fn validate_ending_time(
+    starting_time: u64,
+    ending_time: u64,
+    min_campaign_duration: u64,
+) -> Result<()> {
+    require!(
+        ending_time == 0 || starting_time < ending_time,
+        PlaylinkAirdropErr::InvalidEndingTime
+    );
+
+    // Campaigns without an ending time are never too short, zero disables the minimum
+    require!(
+        ending_time == 0 || ending_time - starting_time >= min_campaign_duration,
+        PlaylinkAirdropErr::DurationTooShort
+    );
+    Ok(())
+}
+
+/// Sum of the SOL asset amounts, which the platform PDA holds until they are airdropped
+fn sol_amount(assets: &[Asset]) -> Result<u64> {
+    assets
+        .iter()
+        .filter(|asset| asset.kind == AssetKind::Sol)
+        .try_fold(0u64, |total, asset| {
+            total.checked_add(asset.available_amount)
+        })
+        .ok_or_else(|| PlaylinkAirdropErr::ArithmeticOverflow.into())
+}
+
+/// Lay out updated assets so that indices already handed out keep pointing at the same asset
+fn stable_asset_order(
+    current: &[Asset],
+    updated: Vec<Asset>,
+    distributed: bool,
+) -> Result<Vec<Asset>> {
+    // An asset of the same kind as an existing one takes its index, the others are appended
+    let mut slots: Vec<Option<Asset>> = vec![None; current.len()];
+    let mut appended = Vec::new();
+    for asset in updated {
+        match (0..current.len())
+            .find(|&index| slots[index].is_none() && current[index].kind == asset.kind)
+        {
+            Some(index) => slots[index] = Some(asset),
+            None => appended.push(asset),
+        }
+    }
+
+    // Assets left out are dropped, which is only allowed while nothing has been distributed
+    require!(
+        !distributed || slots.iter().all(|slot| slot.is_some()),
+        PlaylinkAirdropErr::AssetAlreadyDistributed
+    );
+    Ok(slots.into_iter().flatten().chain(appended).collect())
+}
+
+/// Distinct mints of the token assets, sorted
+fn token_mints(assets: &[Asset]) -> Vec<Pubkey> {
+    let mut mints: Vec<Pubkey> = assets
+        .iter()
+        .filter_map(|asset| match asset.kind {
+            AssetKind::Token { mint } => Some(mint),
+            AssetKind::Sol => None,
+        })
+        .collect();
+    mints.sort();
+    mints.dedup();
+    mints
+}
+
+/// Sum of the asset amounts, failing cleanly instead of overflowing
+fn total_amount(assets: &[Asset]) -> Result<u64> {
+    assets
+        .iter()
+        .try_fold(0u64, |total, asset| {
+            total.checked_add(asset.available_amount)
+        })
+        .ok_or_else(|| PlaylinkAirdropErr::ArithmeticOverflow.into())
+}
+
+/// Campaign ids are part of the receipt and recipient list seeds, which are at most 32 bytes each
+fn validate_campaign_id(campaign_id: &str) -> Result<()> {
+    require!(
+        campaign_id.len() <= MAX_CAMPAIGN_ID_LEN,
+        PlaylinkAirdropErr::CampaignIdTooLong
+    );
+    Ok(())
+}
+
+/// The metadata URI is stored on-chain, so keep it bounded
+fn validate_metadata_uri(metadata_uri: &Option<String>) -> Result<()> {
+    require!(
+        metadata_uri.as_deref().unwrap_or_default().len() <= MAX_METADATA_URI_LEN,
+        PlaylinkAirdropErr::MetadataUriTooLong
+    );
+    Ok(())
+}
+
+/// Categories are short tags for off-chain filtering
+fn validate_category(category: &Option<String>) -> Result<()> {
+    require!(
+        category.as_deref().unwrap_or_default().len() <= MAX_CATEGORY_LEN,
+        PlaylinkAirdropErr::CategoryTooLong
+    );
+    Ok(())
+}
+
+/// Token transfer out of a creator ATA that delegated to the platform PDA, or out of a token account
+/// owned by the platform PDA, which signs by seeds and bump
+fn transfer_asset<'info>(
+    from: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    airdrop_platform: &Account<'info, AirdropPlatform>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    let platform_info = airdrop_platform.to_account_info();
+    invoke_signed(
+        &spl_token::instruction::transfer_checked(
+            &spl_token::ID,
+            from.key,
+            mint.key,
+            to.key,
+            platform_info.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            from.clone(),
+            mint.clone(),
+            to.clone(),
+            platform_info.clone(),
+        ],
+        &[&airdrop_platform.platform_signer_seeds()],
+    )?;
+    Ok(())
+}
+
+/// Find the asset of a batch airdrop, checking everything that does not depend on the recipients
+fn find_batch_asset(
+    accounts: &BatchAirdrop,
+    campaign_id: &str,
+    asset_index: u64,
+) -> Result<(usize, usize)> {
+    let (campaign_index, asset_index) = accounts.airdrop_platform.find_started_asset(
+        campaign_id,
+        accounts.campaign_creator.key(),
+        asset_index,
+        AssetKind::Token {
+            mint: accounts.mint.key(),
+        },
+    )?;
+    let campaign = &accounts.airdrop_platform.all_campaigns[campaign_index];
+    require!(
+        campaign.is_operator(
+            &accounts.airdrop_platform.operators,
+            accounts.operator.key()
+        ),
+        PlaylinkAirdropErr::NotCampaignOperator
+    );
+    campaign.check_creator_cosign(&accounts.campaign_creator)?;
+    require!(!campaign.deposited, PlaylinkAirdropErr::CampaignDeposited);
+
+    // No receipt is kept per recipient, so per-recipient rules cannot be enforced
+    require!(
+        campaign.max_per_recipient == 0
+            && campaign.recipient_cooldown == 0
+            && campaign.vesting_end == 0
+            && campaign.min_holding_mint.is_none(),
+        PlaylinkAirdropErr::BatchNotAllowed
+    );
+
+    // The mint may have been denied since the campaign was created
+    require!(
+        !accounts
+            .airdrop_platform
+            .denied_mints
+            .contains(&accounts.mint.key()),
+        PlaylinkAirdropErr::MintDenied
+    );
+    require!(
+        !campaign.freeze_on_airdrop
+            || accounts.mint.freeze_authority == COption::Some(accounts.airdrop_platform.key()),
+        PlaylinkAirdropErr::MissingFreezeAuthority
+    );
+    Ok((campaign_index, asset_index))
+}
+
+/// Percentage fee cuts go to the platform ATA of the airdropped mint
+fn check_fee_vault(fee_vault: &AccountInfo, platform: Pubkey, mint: Pubkey) -> Result<()> {
+    require!(
+        fee_vault.key() == get_associated_token_address(&platform, &mint),
+        PlaylinkAirdropErr::InvalidFeeVault
+    );
+    Ok(())
+}
+
+/// With a percentage fee, the platform fee vault comes first in the remaining accounts
+fn split_fee_vault<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    accounts: &BatchAirdrop<'info>,
+) -> Result<(Option<&'a AccountInfo<'info>>, &'a [AccountInfo<'info>])> {
+    if accounts.airdrop_platform.fee_bps == 0 {
+        return Ok((None, remaining_accounts));
+    }
+    let (fee_vault, recipient_atas) = remaining_accounts
+        .split_first()
+        .ok_or(PlaylinkAirdropErr::InvalidFeeVault)?;
+    check_fee_vault(
+        fee_vault,
+        accounts.airdrop_platform.key(),
+        accounts.mint.key(),
+    )?;
+    Ok((Some(fee_vault), recipient_atas))
+}
+
+/// Batch recipients must already hold an ATA for the mint, other than the creator's
+fn validate_batch_recipient_ata(
+    recipient_ata: &AccountInfo,
+    accounts: &BatchAirdrop,
+) -> Result<()> {
+    let recipient_ata = Account::<TokenAccount>::try_from(recipient_ata)?;
+    require!(
+        recipient_ata.mint == accounts.mint.key(),
+        PlaylinkAirdropErr::InvalidRecipientAta
+    );
+    require!(
+        recipient_ata.key() != accounts.creator_ata.key(),
+        PlaylinkAirdropErr::SelfAirdrop
+    );
+    Ok(())
+}
+
+/// Airdrop one amount to each recipient ATA, the fee cut going to the fee vault.
+/// PDA signs by seeds and bump.
+fn transfer_shares<'info>(
+    accounts: &mut BatchAirdrop<'info>,
+    fee_vault: Option<&AccountInfo<'info>>,
+    recipient_atas: &[AccountInfo<'info>],
+    amounts: &[u64],
+    decimals: u8,
+    freeze_on_airdrop: bool,
+) -> Result<()> {
+    let mint = accounts.mint.key();
+    for (recipient_ata, &amount) in recipient_atas.iter().zip(amounts) {
+        let fee_amount = accounts.airdrop_platform.take_fee_cut(mint, amount)?;
+        if let Some(fee_vault) = fee_vault.filter(|_| fee_amount > 0) {
+            transfer_asset(
+                &accounts.creator_ata.to_account_info(),
+                &accounts.mint.to_account_info(),
+                fee_vault,
+                &accounts.airdrop_platform,
+                fee_amount,
+                decimals,
+            )?;
+        }
+        if amount > fee_amount {
+            transfer_asset(
+                &accounts.creator_ata.to_account_info(),
+                &accounts.mint.to_account_info(),
+                recipient_ata,
+                &accounts.airdrop_platform,
+                amount - fee_amount,
+                decimals,
+            )?;
+        }
+        if freeze_on_airdrop {
+            set_account_frozen(
+                recipient_ata,
+                &accounts.mint.to_account_info(),
+                &accounts.airdrop_platform,
+                true,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Freeze or thaw a token account, the platform PDA signing as the mint freeze authority
+fn set_account_frozen<'info>(
+    account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    airdrop_platform: &Account<'info, AirdropPlatform>,
+    frozen: bool,
+) -> Result<()> {
+    let platform_info = airdrop_platform.to_account_info();
+    let instruction = if frozen {
+        spl_token::instruction::freeze_account(
+            &spl_token::ID,
+            account.key,
+            mint.key,
+            platform_info.key,
+            &[],
+        )?
+    } else {
+        spl_token::instruction::thaw_account(
+            &spl_token::ID,
+            account.key,
+            mint.key,
+            platform_info.key,
+            &[],
+        )?
+    };
+    invoke_signed(
+        &instruction,
+        &[account.clone(), mint.clone(), platform_info.clone()],
+        &[&airdrop_platform.platform_signer_seeds()],
+    )?;
+    Ok(())
+}
+
+/// Check that `instructions` holds, right before the current instruction, an Ed25519 program
+/// instruction verifying `signature` over `message`, and return the signing public key
+fn verify_ed25519_instruction(
+    instructions: &AccountInfo,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<Pubkey> {
+    let current_index = sysvar::instructions::load_current_index_checked(instructions)?;
+    require!(current_index > 0, PlaylinkAirdropErr::InvalidAuthorization);
+    let ix = sysvar::instructions::load_instruction_at_checked(
+        current_index as usize - 1,
+        instructions,
+    )?;
+    require!(
+        ix.program_id == ed25519_program::ID && ix.accounts.is_empty(),
+        PlaylinkAirdropErr::InvalidAuthorization
+    );
+
+    // Header: signature count, padding, then a single set of offsets into this instruction's data
+    let data = &ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        PlaylinkAirdropErr::InvalidAuthorization
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let (signature_offset, public_key_offset) = (read_u16(2) as usize, read_u16(6) as usize);
+    let (message_offset, message_size) = (read_u16(10) as usize, read_u16(12) as usize);
+    require!(
+        [read_u16(4), read_u16(8), read_u16(14)]
+            .iter()
+            .all(|index| *index == u16::MAX),
+        PlaylinkAirdropErr::InvalidAuthorization
+    );
+    require!(
+        data.get(signature_offset..signature_offset + 64) == Some(&signature[..])
+            && data.get(message_offset..message_offset + message_size) == Some(message),
+        PlaylinkAirdropErr::InvalidAuthorization
+    );
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(PlaylinkAirdropErr::InvalidAuthorization)?;
+    Ok(Pubkey::new(public_key))
+}
+
+/// Lamports held by the platform PDA above its rent-exempt minimum
+fn withdrawable_lamports(airdrop_platform: &AccountInfo) -> Result<u64> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(airdrop_platform.data_len());
+    Ok(airdrop_platform
+        .lamports()
+        .saturating_sub(rent_exempt_minimum))
+}
+
+/// Move lamports from a signer into the platform PDA, skipping empty transfers
+fn transfer_to_platform<'info>(
+    system_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    airdrop_platform: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    system_program::transfer(
+        CpiContext::new(
+            system_program.clone(),
+            system_program::Transfer {
+                from: from.clone(),
+                to: airdrop_platform.clone(),
+            },
+        ),
+        amount,
+    )
+}
+
+/// Counts the bytes written to it, to size an account without allocating its data
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Grow an account once its content no longer fits, the payer covering the extra rent.
+/// The platform grows with its campaigns, receipts with the assets of their campaign.
+fn fit_account<'info, T>(
+    account: &Account<'info, T>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    let mut required_space = ByteCounter(0);
+    account.try_serialize(&mut required_space)?;
+    let account_info = account.to_account_info();
+    if required_space.0 <= account_info.data_len() {
+        return Ok(());
+    }
+    let rent = Rent::get()?;
+    transfer_to_platform(
+        system_program,
+        payer,
+        &account_info,
+        rent.minimum_balance(required_space.0) - rent.minimum_balance(account_info.data_len()),
+    )?;
+    account_info.realloc(required_space.0, true)?;
+    Ok(())
+}
+
+/// Move lamports out of the platform PDA, never letting it drop below the rent-exempt minimum
+fn transfer_from_platform(
+    airdrop_platform: &AccountInfo,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(airdrop_platform.data_len());
+    require!(
+        airdrop_platform.lamports() >= rent_exempt_minimum + amount,
+        PlaylinkAirdropErr::InsufficientPlatformBalance
+    );
+    **airdrop_platform.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+/// None of the token assets may use a mint denied by the operators, and in allowlist mode
+/// every one of them must use a mint the operators approved
+fn validate_mints_allowed(assets: &[Asset], airdrop_platform: &AirdropPlatform) -> Result<()> {
+    for asset in assets {
+        if let AssetKind::Token { mint } = asset.kind {
+            require!(
+                !airdrop_platform.denied_mints.contains(&mint),
+                PlaylinkAirdropErr::MintDenied
+            );
+            require!(
+                !airdrop_platform.allowlist_enabled
+                    || airdrop_platform.allowed_mints.contains(&mint),
+                PlaylinkAirdropErr::MintNotAllowed
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Every token asset must be accompanied by its mint in `mints`, in the same order as the assets.
+/// The declared decimals must match the mint, and NFT assets must reference a mint with
+/// 0 decimals, a supply of 1 and no mint authority, and airdrop exactly one unit. SOL assets need no mint,
+/// but must declare SOL's 9 decimals and cannot be NFTs.
+fn validate_asset_mints(assets: &[Asset], mints: &[AccountInfo]) -> Result<()> {
+    // A campaign without assets has nothing to airdrop
+    require!(!assets.is_empty(), PlaylinkAirdropErr::NoAssets);
+    let mut mints = mints.iter();
+    for asset in assets {
+        let asset_mint = match asset.kind {
+            AssetKind::Token { mint } => mint,
+            AssetKind::Sol => {
+                require!(!asset.is_nft, PlaylinkAirdropErr::InvalidNftAsset);
+                require!(asset.decimals == 9, PlaylinkAirdropErr::DecimalsMismatch);
+                continue;
+            }
+        };
+        let mint_info = mints.next().ok_or(PlaylinkAirdropErr::LengthsMismatch)?;
+        let mint = Account::<Mint>::try_from(mint_info)?;
+        require!(
+            mint.key() == asset_mint,
+            PlaylinkAirdropErr::AssetAddressMismatch
+        );
+        require!(
+            mint.decimals == asset.decimals,
+            PlaylinkAirdropErr::DecimalsMismatch
+        );
+        if asset.is_nft {
+            require!(
+                mint.decimals == 0 && mint.supply == 1 && asset.available_amount == 1,
+                PlaylinkAirdropErr::InvalidNftAsset
+            );
+            require!(
+                mint.mint_authority.is_none(),
+                PlaylinkAirdropErr::MintableNftRejected
+            );
+        }
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(platform_id: String)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        seeds = [PLATFORM_SEED, platform_id.as_bytes()],
+        bump,
+        payer = payer,
+        space = PLATFORM_SPACE
+    )]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub admin: Signer<'info>,
+    /// Funds the platform account, which may be the admin itself or a separate funding wallet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(platform_id: String)]
+pub struct InitializeIfNeeded<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [PLATFORM_SEED, platform_id.as_bytes()],
+        bump,
+        payer = admin,
+        space = PLATFORM_SPACE
+    )]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePlatform<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Its layout may predate the current one, so it is deserialized by the instruction.
+    /// Only this program creates accounts it owns with the platform discriminator.
+    #[account(mut, owner = crate::ID)]
+    pub airdrop_platform: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetOperators<'info> {
+    #[account(mut, constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClearOperators<'info> {
+    #[account(mut, constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeBounds<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEditGracePeriod<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinCampaignDuration<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetDustThreshold<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimFee<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetDebugLogging<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetKeepDrainedCampaigns<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxTotalCampaigns<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeePerAsset<'info> {
+    #[account(
+        constraint = operator.key() == airdrop_platform.admin.key()
+            || airdrop_platform.operators.iter().any(|op| op.key() == operator.key())
+    )]
+    pub operator: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeExempt<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeBps<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeePerAssetPerDay<'info> {
+    #[account(
+        constraint = operator.key() == airdrop_platform.admin.key()
+            || airdrop_platform.operators.iter().any(|op| op.key() == operator.key())
+    )]
+    pub operator: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAirdropCampaign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut)]
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCampaignsBatch<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut)]
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAirdropCampaignWaived<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut, constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
+    pub operator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCampaign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut)]
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RescheduleCampaign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferCampaign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpAsset<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut)]
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecipientCooldown<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCampaignOperators<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowedDistributors<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimWindow<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRequireCreatorCosign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinHolding<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFreezeOnAirdrop<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetVesting<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReauthorizeDelegations<'info> {
+    pub campaign_creator: Signer<'info>,
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DepositAssets<'info> {
+    pub campaign_creator: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnusedDeposit<'info> {
+    #[account(mut, token::mint = mint, token::authority = campaign_creator)]
+    pub creator_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = vault.key() == get_associated_token_address(&airdrop_platform.key(), &mint.key())
+            @ PlaylinkAirdropErr::InvalidVault
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    pub campaign_creator: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UnfreezeRecipient<'info> {
+    #[account(mut, token::mint = mint)]
+    pub recipient_ata: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
+    pub operator: Signer<'info>,
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintDenied<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
+    pub operator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlistEnabled<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
+    pub operator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintAllowed<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
+    pub operator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCampaignPaused<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
+    pub operator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminCloseCampaign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    /// CHECK: This is safe because it is checked against the campaign creator
+    #[account(mut)]
+    pub campaign_creator: AccountInfo<'info>,
+    #[account(constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
+    pub operator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PurgeCompleted<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+pub struct SplitCampaign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut)]
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MergeCampaigns<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut)]
+    pub campaign_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        seeds = [b"airdrop_platform"],
-        bump,
-        payer = admin,
-        space = 9000
-    )]
+pub struct CancelCampaign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
     pub airdrop_platform: Account<'info, AirdropPlatform>,
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub campaign_creator: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SetOperators<'info> {
-    #[account(mut, constraint = admin.key() == airdrop_platform.admin.key())]
-    pub admin: Signer<'info>,
-    #[account(mut, seeds = [b"airdrop_platform"], bump = airdrop_platform.bump)]
+pub struct ReclaimCampaign<'info> {
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
     pub airdrop_platform: Account<'info, AirdropPlatform>,
+    #[account(mut)]
+    pub campaign_creator: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SetFeePerAsset<'info> {
-    #[account(constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
+#[instruction(campaign_id: String)]
+pub struct Airdrop<'info> {
+    #[account(mut, token::mint = mint)]
+    pub creator_ata: Account<'info, TokenAccount>,
+    /// CHECK: This is the recipient's ATA, checked by address and created if it does not exist yet
+    #[account(
+        mut,
+        constraint = recipient_ata.key() != creator_ata.key() @ PlaylinkAirdropErr::SelfAirdrop,
+        constraint = recipient_ata.key() == get_associated_token_address(&recipient.key(), &mint.key())
+            @ PlaylinkAirdropErr::InvalidRecipientAta
+    )]
+    pub recipient_ata: AccountInfo<'info>,
+    /// CHECK: This is safe because we never change its content
+    pub recipient: AccountInfo<'info>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: This is safe because we never change its content
+    pub campaign_creator: AccountInfo<'info>,
+    #[account(mut)]
     pub operator: Signer<'info>,
-    #[account(mut, seeds = [b"airdrop_platform"], bump = airdrop_platform.bump)]
+    #[account(
+        init_if_needed,
+        seeds = [b"receipt", airdrop_platform.key().as_ref(), campaign_creator.key().as_ref(), campaign_id.as_bytes(), recipient.key().as_ref()],
+        bump,
+        payer = operator,
+        space = RECEIPT_SPACE
+    )]
+    pub receipt: Account<'info, RecipientReceipt>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
     pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CreateAirdropCampaign<'info> {
-    #[account(mut, seeds = [b"airdrop_platform"], bump = airdrop_platform.bump)]
-    pub airdrop_platform: Account<'info, AirdropPlatform>,
+#[instruction(campaign_id: String)]
+pub struct AirdropAllAssets<'info> {
+    /// CHECK: This is safe because it only receives lamports
     #[account(mut)]
-    pub campaign_creator: Signer<'info>,
+    pub recipient: AccountInfo<'info>,
+    /// CHECK: This is safe because we never change its content
+    pub campaign_creator: AccountInfo<'info>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [b"receipt", airdrop_platform.key().as_ref(), campaign_creator.key().as_ref(), campaign_id.as_bytes(), recipient.key().as_ref()],
+        bump,
+        payer = operator,
+        space = RECEIPT_SPACE
+    )]
+    pub receipt: Account<'info, RecipientReceipt>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateCampaign<'info> {
-    #[account(mut, seeds = [b"airdrop_platform"], bump = airdrop_platform.bump)]
+pub struct BatchAirdrop<'info> {
+    #[account(mut, token::mint = mint, token::authority = campaign_creator)]
+    pub creator_ata: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: This is safe because we never change its content
+    pub campaign_creator: AccountInfo<'info>,
+    pub operator: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: String)]
+pub struct UploadRecipients<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"recipients", airdrop_platform.key().as_ref(), campaign_creator.key().as_ref(), campaign_id.as_bytes()],
+        bump,
+        payer = operator,
+        space = RECIPIENT_LIST_SPACE
+    )]
+    pub recipient_list: Account<'info, RecipientList>,
+    /// CHECK: This is safe because we never change its content
+    pub campaign_creator: AccountInfo<'info>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
     pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: String)]
+pub struct DistributeChunk<'info> {
+    pub batch: BatchAirdrop<'info>,
+    #[account(
+        mut,
+        seeds = [b"recipients", batch.airdrop_platform.key().as_ref(), batch.campaign_creator.key().as_ref(), campaign_id.as_bytes()],
+        bump = recipient_list.bump
+    )]
+    pub recipient_list: Account<'info, RecipientList>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: String)]
+pub struct AirdropSol<'info> {
+    /// CHECK: This is safe because it only receives lamports
     #[account(mut)]
-    pub campaign_creator: Signer<'info>,
+    pub recipient: AccountInfo<'info>,
+    /// CHECK: This is safe because we never change its content
+    pub campaign_creator: AccountInfo<'info>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [b"receipt", airdrop_platform.key().as_ref(), campaign_creator.key().as_ref(), campaign_id.as_bytes(), recipient.key().as_ref()],
+        bump,
+        payer = operator,
+        space = RECEIPT_SPACE
+    )]
+    pub receipt: Account<'info, RecipientReceipt>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Airdrop<'info> {
+#[instruction(campaign_id: String)]
+pub struct SimulateAirdrop<'info> {
+    #[account(token::mint = mint, token::authority = campaign_creator)]
+    pub creator_ata: Account<'info, TokenAccount>,
+    /// CHECK: This is the recipient's ATA, checked by address and only read
+    #[account(
+        constraint = recipient_ata.key() == get_associated_token_address(&recipient.key(), &mint.key())
+            @ PlaylinkAirdropErr::InvalidRecipientAta
+    )]
+    pub recipient_ata: AccountInfo<'info>,
+    /// CHECK: This is safe because we never change its content
+    pub recipient: AccountInfo<'info>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: This is safe because we never change its content
+    pub campaign_creator: AccountInfo<'info>,
+    /// CHECK: This is the recipient's receipt, checked by seeds, which may not exist yet
+    #[account(
+        seeds = [b"receipt", airdrop_platform.key().as_ref(), campaign_creator.key().as_ref(), campaign_id.as_bytes(), recipient.key().as_ref()],
+        bump
+    )]
+    pub receipt: AccountInfo<'info>,
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: String)]
+pub struct ClaimWithAuthorization<'info> {
     #[account(mut, token::mint = mint, token::authority = campaign_creator)]
     pub creator_ata: Account<'info, TokenAccount>,
-    #[account(mut, token::mint = mint)]
+    #[account(mut, token::mint = mint, token::authority = recipient)]
     pub recipient_ata: Account<'info, TokenAccount>,
     pub mint: Account<'info, Mint>,
     /// CHECK: This is safe because we never change its content
     pub campaign_creator: AccountInfo<'info>,
-    #[account(constraint = airdrop_platform.operators.iter().any(|op| op.key() == operator.key()))]
-    pub operator: Signer<'info>,
-    #[account(mut, seeds = [b"airdrop_platform"], bump = airdrop_platform.bump)]
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [b"receipt", airdrop_platform.key().as_ref(), campaign_creator.key().as_ref(), campaign_id.as_bytes(), recipient.key().as_ref()],
+        bump,
+        payer = recipient,
+        space = RECEIPT_SPACE
+    )]
+    pub receipt: Account<'info, RecipientReceipt>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
     pub airdrop_platform: Account<'info, AirdropPlatform>,
+    /// CHECK: This is the instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
     pub rent: Sysvar<'info, Rent>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct AdminSweepTokens<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, token::mint = mint, token::authority = airdrop_platform)]
+    pub source: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawableFees<'info> {
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+pub struct GetAssetAmounts<'info> {
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: String, creator: Pubkey, asset_index: u64, recipient: Pubkey)]
+pub struct ClaimableAmount<'info> {
+    /// CHECK: This is the recipient's receipt, checked by seeds, which may not exist yet
+    #[account(
+        seeds = [b"receipt", airdrop_platform.key().as_ref(), creator.as_ref(), campaign_id.as_bytes(), recipient.as_ref()],
+        bump
+    )]
+    pub receipt: AccountInfo<'info>,
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+pub struct GetCreatorCampaigns<'info> {
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: String, creator: Pubkey, recipient: Pubkey)]
+pub struct HasClaimed<'info> {
+    /// CHECK: This is the recipient's receipt, checked by seeds, which may not exist yet
+    #[account(
+        seeds = [b"receipt", airdrop_platform.key().as_ref(), creator.as_ref(), campaign_id.as_bytes(), recipient.as_ref()],
+        bump
+    )]
+    pub receipt: AccountInfo<'info>,
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+pub struct GetRecentWithdrawals<'info> {
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+pub struct GetOperatorActivity<'info> {
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+pub struct ListCampaignIds<'info> {
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
+#[derive(Accounts)]
+pub struct ListOperators<'info> {
+    #[account(seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+}
+
 #[derive(Accounts)]
 pub struct WithdrawAirdropFee<'info> {
     /// CHECK: This is safe
@@ -352,35 +4350,667 @@ pub struct WithdrawAirdropFee<'info> {
     pub recipient: AccountInfo<'info>,
     #[account(mut, constraint = admin.key() == airdrop_platform.admin.key())]
     pub admin: Signer<'info>,
-    #[account(mut, seeds = [b"airdrop_platform"], bump = airdrop_platform.bump)]
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
     pub airdrop_platform: Account<'info, AirdropPlatform>,
     pub system_program: Program<'info, System>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[derive(Accounts)]
+pub struct SetWithdrawalDelay<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestWithdrawal<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteWithdrawal<'info> {
+    /// CHECK: This is safe
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+    #[account(mut, constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdrawal<'info> {
+    #[account(constraint = admin.key() == airdrop_platform.admin.key())]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [PLATFORM_SEED, airdrop_platform.platform_id.as_bytes()], bump = airdrop_platform.bump)]
+    pub airdrop_platform: Account<'info, AirdropPlatform>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    Token { mint: Pubkey },
+    Sol,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub struct Asset {
-    asset_address: Pubkey,
+    kind: AssetKind,
     available_amount: u64,
+    is_nft: bool,
+    decimals: u8,
+}
+
+/// A recipient of an uploaded list and the amount it is airdropped
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RecipientAmount {
+    recipient: Pubkey,
+    amount: u64,
+}
+
+/// Arguments of a single campaign created by `create_campaigns_batch`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CampaignInput {
+    campaign_id: String,
+    assets: Vec<Asset>,
+    starting_time: u64,
+    ending_time: u64,
+    metadata_uri: Option<String>,
+    category: Option<String>,
+    max_per_recipient: u64,
+}
+
+/// When an operator last signed an airdrop or a fee update, for monitoring
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct OperatorActivity {
+    operator: Pubkey,
+    last_active: i64,
+}
+
+/// Percentage fees collected in the platform vault of a mint, and not swept yet
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TokenFee {
+    mint: Pubkey,
+    amount: u64,
+}
+
+/// A past fee withdrawal, for the treasury audit trail
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeWithdrawal {
+    timestamp: i64,
+    recipient: Pubkey,
+    amount: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct AirdropCampaign {
     campaign_id: String,
     creator: Pubkey,
-    assets: Vec<Asset>,
+    assets: Vec<Asset>,
+    starting_time: u64,
+    total_available_assets: u64,
+    airdrop_fee: u64,
+    ending_time: u64,
+    metadata_uri: Option<String>,
+    category: Option<String>,
+    max_per_recipient: u64,
+    total_distributed: u64,
+    paused: bool,
+    completed: bool,
+    recipient_cooldown: u64,
+    freeze_on_airdrop: bool,
+    vesting_start: u64,
+    vesting_end: u64,
+    claim_window: u64,
+    campaign_operators: Vec<Pubkey>,
+    distributed_count: u64,
+    next_index: u64,
+    require_creator_cosign: bool,
+    deposited: bool,
+    min_holding_mint: Option<Pubkey>,
+    min_holding_amount: u64,
+    fee_per_asset: u64,
+    allowed_distributors: Vec<Pubkey>,
+    fee_per_asset_per_day: u64,
+    completed_at: i64,
+}
+
+impl AirdropCampaign {
+    /// Campaigns with operators of their own are airdropped by them only,
+    /// the others by the platform operators. Allowed distributors, if any,
+    /// further restrict them to the platform operators on that list.
+    fn is_operator(&self, platform_operators: &[Pubkey], operator: Pubkey) -> bool {
+        let is_operator = if self.campaign_operators.is_empty() {
+            platform_operators.contains(&operator)
+        } else {
+            self.campaign_operators.contains(&operator)
+        };
+        is_operator
+            && (self.allowed_distributors.is_empty()
+                || (platform_operators.contains(&operator)
+                    && self.allowed_distributors.contains(&operator)))
+    }
+
+    /// Creators who do not trust the operators co-sign each airdrop of their campaign
+    fn check_creator_cosign(&self, campaign_creator: &AccountInfo) -> Result<()> {
+        require!(
+            !self.require_creator_cosign || campaign_creator.is_signer,
+            PlaylinkAirdropErr::CreatorCosignRequired
+        );
+        Ok(())
+    }
+
+    /// Deposited campaigns airdrop out of the platform vault of the mint, the others
+    /// out of the creator's own ATA
+    fn check_source_ata(&self, source: &Account<TokenAccount>, platform: Pubkey) -> Result<()> {
+        if self.deposited {
+            require!(
+                source.key() == get_associated_token_address(&platform, &source.mint),
+                PlaylinkAirdropErr::InvalidVault
+            );
+        } else {
+            require!(
+                source.owner == self.creator,
+                PlaylinkAirdropErr::InvalidCreatorAta
+            );
+        }
+        Ok(())
+    }
+
+    /// Holder-only campaigns require the recipient's token account of the holding mint,
+    /// passed as the last remaining account, to hold the minimum amount
+    fn check_holding(&self, remaining_accounts: &[AccountInfo], recipient: Pubkey) -> Result<()> {
+        let min_holding_mint = match self.min_holding_mint {
+            Some(mint) => mint,
+            None => return Ok(()),
+        };
+        let holding_info = remaining_accounts
+            .last()
+            .ok_or(PlaylinkAirdropErr::IneligibleHolder)?;
+        let holding = Account::<TokenAccount>::try_from(holding_info)?;
+        require!(
+            holding.owner == recipient
+                && holding.mint == min_holding_mint
+                && holding.amount >= self.min_holding_amount,
+            PlaylinkAirdropErr::IneligibleHolder
+        );
+        Ok(())
+    }
+
+    /// The fee is earned once the campaign completed or ended, until then cancelling
+    /// or force-closing the campaign refunds it
+    fn fee_refundable(&self, now: u64) -> bool {
+        !self.completed && (self.ending_time == 0 || now <= self.ending_time)
+    }
+
+    /// Tokens deposited and not airdropped yet have to be withdrawn before the campaign goes away
+    fn holds_deposit(&self) -> bool {
+        self.deposited
+            && self
+                .assets
+                .iter()
+                .any(|asset| asset.kind != AssetKind::Sol && asset.available_amount > 0)
+    }
+
+    /// Amount of an asset a recipient can claim at `now`, given what it already claimed.
+    /// Without a vesting schedule, everything still available is claimable.
+    fn claimable_amount(&self, asset_index: usize, claimed: u64, now: u64) -> u64 {
+        let available = self.assets[asset_index].available_amount;
+        if self.vesting_end == 0 || now >= self.vesting_end {
+            return available;
+        }
+        if now < self.vesting_start {
+            return 0;
+        }
+
+        // Vest linearly what the recipient could have received in total
+        let total = (available + claimed) as u128;
+        let vested = total * (now - self.vesting_start) as u128
+            / (self.vesting_end - self.vesting_start) as u128;
+        (vested as u64).saturating_sub(claimed).min(available)
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct AirdropPlatform {
+    admin: Pubkey,
+    fee_per_asset: u64,
+    all_campaigns: Vec<AirdropCampaign>,
+    operators: Vec<Pubkey>,
+    bump: u8,
+    min_fee_per_asset: u64,
+    max_fee_per_asset: u64,
+    campaign_counter: u64,
+    edit_grace_period: u64,
+    schema_version: u8,
+    platform_id: String,
+    accumulated_fees: u64,
+    denied_mints: Vec<Pubkey>,
+    fee_per_asset_per_day: u64,
+    operator_activity: Vec<OperatorActivity>,
+    keep_drained_campaigns: bool,
+    max_total_campaigns: u32,
+    withdrawal_delay: u64,
+    pending_withdrawal: u64,
+    withdrawal_requested_at: i64,
+    fee_bps: u16,
+    fee_exempt: Vec<Pubkey>,
+    recent_withdrawals: Vec<FeeWithdrawal>,
+    min_campaign_duration: u64,
+    claim_fee: u64,
+    debug_logging: bool,
+    allowlist_enabled: bool,
+    allowed_mints: Vec<Pubkey>,
+    token_fees: Vec<TokenFee>,
+    dust_threshold: u64,
+}
+
+/// Platform layout from before the per-platform settings, to migrate the platforms created back then
+#[derive(AnchorDeserialize)]
+struct LegacyAirdropPlatform {
+    admin: Pubkey,
+    fee_per_asset: u64,
+    all_campaigns: Vec<LegacyAirdropCampaign>,
+    operators: Vec<Pubkey>,
+    bump: u8,
+}
+
+#[derive(AnchorDeserialize)]
+struct LegacyAirdropCampaign {
+    campaign_id: String,
+    creator: Pubkey,
+    assets: Vec<LegacyAsset>,
     starting_time: u64,
     total_available_assets: u64,
     airdrop_fee: u64,
 }
 
+/// Legacy assets were all tokens, identified by their mint
+#[derive(AnchorDeserialize)]
+struct LegacyAsset {
+    asset_address: Pubkey,
+    available_amount: u64,
+}
+
+impl LegacyAirdropPlatform {
+    /// Convert to the current layout, reading the decimals of every asset from its mint
+    /// among `mints`. Campaigns keep the fee they paid, at the fee per asset it amounts to.
+    fn migrate(self, mints: &[AccountInfo]) -> Result<AirdropPlatform> {
+        let mut all_campaigns = Vec::with_capacity(self.all_campaigns.len());
+        for campaign in self.all_campaigns {
+            let mut assets = Vec::with_capacity(campaign.assets.len());
+            for asset in campaign.assets {
+                let mint_info = mints
+                    .iter()
+                    .find(|mint| mint.key() == asset.asset_address)
+                    .ok_or(PlaylinkAirdropErr::AssetAddressMismatch)?;
+                assets.push(Asset {
+                    kind: AssetKind::Token {
+                        mint: asset.asset_address,
+                    },
+                    available_amount: asset.available_amount,
+                    is_nft: false,
+                    decimals: Account::<Mint>::try_from(mint_info)?.decimals,
+                });
+            }
+            let fee_per_asset = campaign.airdrop_fee / assets.len().max(1) as u64;
+            all_campaigns.push(AirdropCampaign {
+                campaign_id: campaign.campaign_id,
+                creator: campaign.creator,
+                assets,
+                starting_time: campaign.starting_time,
+                total_available_assets: campaign.total_available_assets,
+                airdrop_fee: campaign.airdrop_fee,
+                fee_per_asset,
+                ..Default::default()
+            });
+        }
+
+        // Legacy campaigns were kept in creation order, lookups expect them sorted by creator and id
+        all_campaigns.sort_by(|a, b| {
+            (a.creator, a.campaign_id.as_str()).cmp(&(b.creator, b.campaign_id.as_str()))
+        });
+        Ok(AirdropPlatform {
+            admin: self.admin,
+            fee_per_asset: self.fee_per_asset,
+            all_campaigns,
+            operators: self.operators,
+            bump: self.bump,
+            min_fee_per_asset: self.fee_per_asset,
+            max_fee_per_asset: self.fee_per_asset,
+            ..Default::default()
+        })
+    }
+}
+
+/// Recipients of a campaign uploaded ahead of time, distributed one chunk at a time
 #[account]
 #[derive(Default)]
-pub struct AirdropPlatform {
-    admin: Pubkey,
-    fee_per_asset: u64,
-    all_campaigns: Vec<AirdropCampaign>,
-    operators: Vec<Pubkey>,
+pub struct RecipientList {
+    chunks: Vec<Vec<RecipientAmount>>,
+    next_chunk: u32,
+    bump: u8,
+}
+
+/// Per-recipient record of what has been claimed from a campaign, per asset index
+#[account]
+#[derive(Default)]
+pub struct RecipientReceipt {
+    claimed: Vec<u64>,
+    last_authorization_expiry: u64,
     bump: u8,
+    last_claim_time: i64,
+}
+
+impl RecipientReceipt {
+    /// Amount of an asset received so far
+    fn claimed(&self, asset_index: usize) -> u64 {
+        self.claimed.get(asset_index).copied().unwrap_or_default()
+    }
+
+    /// Add the amounts received from some assets at once, rejecting them if they exceed
+    /// the campaign cap (zero means no cap), have not vested yet, or come within the campaign
+    /// cooldown after the previous ones (zero means no cooldown).
+    /// Assets may be of different mints, so each one is capped and vested on its own.
+    fn record(
+        &mut self,
+        claims: &[(usize, u64)],
+        campaign: &AirdropCampaign,
+        bump: u8,
+    ) -> Result<()> {
+        let now = current_timestamp()?;
+        if self.claimed.len() < campaign.assets.len() {
+            self.claimed.resize(campaign.assets.len(), 0);
+        }
+        for &(asset_index, amount) in claims {
+            let claimed = self.claimed[asset_index];
+            require!(
+                amount <= campaign.claimable_amount(asset_index, claimed, now as u64),
+                PlaylinkAirdropErr::AmountNotVested
+            );
+            let claimed = claimed
+                .checked_add(amount)
+                .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?;
+            require!(
+                campaign.max_per_recipient == 0 || claimed <= campaign.max_per_recipient,
+                PlaylinkAirdropErr::RecipientCapExceeded
+            );
+            self.claimed[asset_index] = claimed;
+        }
+        // A cooldown too long to add up keeps the recipient waiting for good
+        let cooldown_end = self
+            .last_claim_time
+            .saturating_add(i64::try_from(campaign.recipient_cooldown).unwrap_or(i64::MAX));
+        require!(
+            self.last_claim_time == 0 || now >= cooldown_end,
+            PlaylinkAirdropErr::CooldownActive
+        );
+        self.bump = bump;
+        self.last_claim_time = now;
+        Ok(())
+    }
+}
+
+impl AirdropPlatform {
+    /// Seeds the platform PDA signs with, the same it is derived from in the account constraints
+    fn platform_signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            PLATFORM_SEED,
+            self.platform_id.as_bytes(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    /// Campaigns are keyed by creator and id, different creators may reuse the same id.
+    /// They are kept sorted by that key, so lookups are a binary search.
+    /// Returns the campaign position, or the position to insert it at if it does not exist.
+    fn find_campaign(
+        &self,
+        creator: Pubkey,
+        campaign_id: &str,
+    ) -> std::result::Result<usize, usize> {
+        self.all_campaigns
+            .binary_search_by(|c| (c.creator, c.campaign_id.as_str()).cmp(&(creator, campaign_id)))
+    }
+
+    /// Stamp the current time as the last activity of an operator
+    fn record_activity(&mut self, operator: Pubkey) -> Result<()> {
+        let last_active = current_timestamp()?;
+        match self
+            .operator_activity
+            .iter_mut()
+            .find(|activity| activity.operator == operator)
+        {
+            Some(activity) => activity.last_active = last_active,
+            None => self.operator_activity.push(OperatorActivity {
+                operator,
+                last_active,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Zero the amounts left in the other assets of a campaign when they are all token dust,
+    /// below the platform threshold. Only campaigns airdropping from the creator's wallet are swept:
+    /// their dust stays with the creator, while deposited tokens and SOL would be stranded on the platform.
+    /// Returns the amount swept from every asset.
+    fn sweep_dust(&mut self, campaign_index: usize, drained_index: usize) -> Vec<u64> {
+        let dust_threshold = self.dust_threshold;
+        let campaign = &mut self.all_campaigns[campaign_index];
+        let mut swept = vec![0; campaign.assets.len()];
+        let is_dust = campaign
+            .assets
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != drained_index)
+            .all(|(_, asset)| {
+                asset.available_amount == 0
+                    || (asset.kind != AssetKind::Sol && asset.available_amount < dust_threshold)
+            });
+        if campaign.deposited || !is_dust {
+            return swept;
+        }
+        for (index, asset) in campaign.assets.iter_mut().enumerate() {
+            if index != drained_index {
+                swept[index] = asset.available_amount;
+                asset.available_amount = 0;
+            }
+        }
+        swept
+    }
+
+    /// Fees left to withdraw at `now`, leaving aside those that creators may still get back
+    fn withdrawable_fees(&self, now: i64) -> Result<u64> {
+        let refundable_fees = self
+            .all_campaigns
+            .iter()
+            .filter(|campaign| campaign.fee_refundable(now as u64))
+            .try_fold(0u64, |total, campaign| {
+                total.checked_add(campaign.airdrop_fee)
+            })
+            .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?;
+        Ok(self.accumulated_fees.saturating_sub(refundable_fees))
+    }
+
+    /// Keep the most recent fee withdrawals only, evicting the oldest
+    fn record_withdrawal(&mut self, recipient: Pubkey, amount: u64) -> Result<()> {
+        if self.recent_withdrawals.len() >= MAX_RECENT_WITHDRAWALS {
+            self.recent_withdrawals.remove(0);
+        }
+        self.recent_withdrawals.push(FeeWithdrawal {
+            timestamp: current_timestamp()?,
+            recipient,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Flat fee per asset at the given rate, plus a daily fee per asset over the campaign duration at the given
+    /// daily rate when the campaign has an ending time. Partial days are charged as full days.
+    #[allow(clippy::too_many_arguments)]
+    fn campaign_fee(
+        &self,
+        creator: Pubkey,
+        fee_per_asset: u64,
+        fee_per_asset_per_day: u64,
+        asset_count: usize,
+        starting_time: u64,
+        ending_time: u64,
+    ) -> Result<u64> {
+        // Trusted creators pay nothing, and a cut of the airdropped tokens replaces the SOL fee
+        if self.fee_exempt.contains(&creator) || self.fee_bps > 0 {
+            return Ok(0);
+        }
+        let asset_count = asset_count as u64;
+        let flat_fee = fee_per_asset * asset_count;
+        if fee_per_asset_per_day == 0 || ending_time == 0 {
+            return Ok(flat_fee);
+        }
+        let duration = ending_time.saturating_sub(starting_time);
+        let duration_days = duration / SECONDS_PER_DAY + (duration % SECONDS_PER_DAY > 0) as u64;
+        fee_per_asset_per_day
+            .checked_mul(asset_count)
+            .and_then(|fee| fee.checked_mul(duration_days))
+            .and_then(|fee| fee.checked_add(flat_fee))
+            .ok_or_else(|| PlaylinkAirdropErr::ArithmeticOverflow.into())
+    }
+
+    /// Locate an asset of a started campaign, checking it matches the given creator and asset kind.
+    /// Returns the campaign and asset positions.
+    fn find_started_asset(
+        &self,
+        campaign_id: &str,
+        creator: Pubkey,
+        asset_index: u64,
+        kind: AssetKind,
+    ) -> Result<(usize, usize)> {
+        let campaign_index = self.find_started_campaign(campaign_id, creator)?;
+        let campaign = &self.all_campaigns[campaign_index];
+
+        // Find corresponding assets
+        require!(
+            asset_index < campaign.assets.len() as u64,
+            PlaylinkAirdropErr::InvalidAssetIndex
+        );
+        let asset = &campaign.assets[asset_index as usize];
+        require!(asset.kind == kind, PlaylinkAirdropErr::AssetAddressMismatch);
+
+        // Other assets of the campaign may still be available, but not this one
+        require!(
+            asset.available_amount > 0,
+            PlaylinkAirdropErr::AssetDepleted
+        );
+        require!(
+            !asset.is_nft || asset.available_amount == 1,
+            PlaylinkAirdropErr::InvalidNftAsset
+        );
+
+        Ok((campaign_index, asset_index as usize))
+    }
+
+    /// Find a campaign of the given creator that is currently running
+    fn find_started_campaign(&self, campaign_id: &str, creator: Pubkey) -> Result<usize> {
+        // Make sure that the campaign exists
+        let campaign_index = self
+            .find_campaign(creator, campaign_id)
+            .map_err(|_| PlaylinkAirdropErr::CampaignNotExists)?;
+        let campaign = &self.all_campaigns[campaign_index];
+        require!(
+            campaign.creator == creator,
+            PlaylinkAirdropErr::CampaignNotExists
+        );
+
+        // Make sure that this campaign has started, and has not ended yet
+        let now = current_timestamp()? as u64;
+        require!(
+            now >= campaign.starting_time,
+            PlaylinkAirdropErr::CampaignNotStarts
+        );
+        require!(
+            campaign.ending_time == 0 || now < campaign.ending_time,
+            PlaylinkAirdropErr::CampaignEnded
+        );
+        require!(!campaign.completed, PlaylinkAirdropErr::CampaignCompleted);
+        require!(!campaign.paused, PlaylinkAirdropErr::CampaignPaused);
+
+        Ok(campaign_index)
+    }
+
+    /// Deduct a distributed amount from an asset, completing the campaign once all assets are airdropped:
+    /// it is then removed, or kept as a record if the platform keeps drained campaigns.
+    /// Returns the total amount distributed by the campaign so far.
+    fn consume_asset(
+        &mut self,
+        campaign_index: usize,
+        asset_index: usize,
+        amount: u64,
+    ) -> Result<u64> {
+        let campaign = &mut self.all_campaigns[campaign_index];
+        campaign.assets[asset_index].available_amount -= amount;
+        campaign.total_distributed += amount;
+        let total_distributed = campaign.total_distributed;
+
+        // Recompute the total from the live asset amounts rather than trusting the running counter
+        campaign.total_available_assets = campaign
+            .assets
+            .iter()
+            .map(|asset| asset.available_amount)
+            .sum();
+        if campaign
+            .assets
+            .iter()
+            .all(|asset| asset.available_amount == 0)
+        {
+            if self.keep_drained_campaigns {
+                campaign.completed = true;
+                campaign.completed_at = current_timestamp()?;
+            } else {
+                self.all_campaigns.remove(campaign_index);
+            }
+        }
+        Ok(total_distributed)
+    }
+
+    /// Cut of an airdropped token amount kept as the percentage fee, zero with the flat SOL fee.
+    /// It is recorded with the fees collected in the platform vault of its mint.
+    fn take_fee_cut(&mut self, mint: Pubkey, amount: u64) -> Result<u64> {
+        let fee_amount = (amount as u128 * self.fee_bps as u128 / 10000) as u64;
+        if fee_amount == 0 {
+            return Ok(0);
+        }
+        match self.token_fees.iter_mut().find(|fee| fee.mint == mint) {
+            Some(fee) => {
+                fee.amount = fee
+                    .amount
+                    .checked_add(fee_amount)
+                    .ok_or(PlaylinkAirdropErr::ArithmeticOverflow)?
+            }
+            None => self.token_fees.push(TokenFee {
+                mint,
+                amount: fee_amount,
+            }),
+        }
+        Ok(fee_amount)
+    }
+
+    /// Tokens of a mint the vault holds on behalf of deposited campaigns
+    fn deposited_amount(&self, mint: Pubkey) -> u64 {
+        self.all_campaigns
+            .iter()
+            .filter(|campaign| campaign.deposited)
+            .flat_map(|campaign| campaign.assets.iter())
+            .filter(|asset| asset.kind == AssetKind::Token { mint })
+            .map(|asset| asset.available_amount)
+            .sum()
+    }
 }
 
 #[error_code]
@@ -411,6 +5041,207 @@ pub enum PlaylinkAirdropErr {
 
     #[msg("PlaylinkAirdrop: asset address mismatch")]
     AssetAddressMismatch,
+
+    #[msg("PlaylinkAirdrop: insufficient creator balance")]
+    InsufficientCreatorBalance,
+
+    #[msg("PlaylinkAirdrop: invalid NFT asset")]
+    InvalidNftAsset,
+
+    #[msg("PlaylinkAirdrop: fee out of bounds")]
+    FeeOutOfBounds,
+
+    #[msg("PlaylinkAirdrop: decimals mismatch")]
+    DecimalsMismatch,
+
+    #[msg("PlaylinkAirdrop: insufficient platform balance")]
+    InsufficientPlatformBalance,
+
+    #[msg("PlaylinkAirdrop: authorization expired")]
+    AuthorizationExpired,
+
+    #[msg("PlaylinkAirdrop: authorization already used")]
+    AuthorizationAlreadyUsed,
+
+    #[msg("PlaylinkAirdrop: invalid authorization")]
+    InvalidAuthorization,
+
+    #[msg("PlaylinkAirdrop: invalid claim amount")]
+    InvalidClaimAmount,
+
+    #[msg("PlaylinkAirdrop: metadata URI too long")]
+    MetadataUriTooLong,
+
+    #[msg("PlaylinkAirdrop: recipient cap exceeded")]
+    RecipientCapExceeded,
+
+    #[msg("PlaylinkAirdrop: starting time too far in the future")]
+    ScheduleTooFar,
+
+    #[msg("PlaylinkAirdrop: arithmetic overflow")]
+    ArithmeticOverflow,
+
+    #[msg("PlaylinkAirdrop: campaign paused")]
+    CampaignPaused,
+
+    #[msg("PlaylinkAirdrop: invalid recipient ATA")]
+    InvalidRecipientAta,
+
+    #[msg("PlaylinkAirdrop: mint denied")]
+    MintDenied,
+
+    #[msg("PlaylinkAirdrop: ending time must be after starting time")]
+    InvalidEndingTime,
+
+    #[msg("PlaylinkAirdrop: campaign ended")]
+    CampaignEnded,
+
+    #[msg("PlaylinkAirdrop: insufficient delegation to the platform")]
+    InsufficientDelegation,
+
+    #[msg("PlaylinkAirdrop: campaign completed")]
+    CampaignCompleted,
+
+    #[msg("PlaylinkAirdrop: too many campaigns in a batch")]
+    BatchTooLarge,
+
+    #[msg("PlaylinkAirdrop: insufficient balance to pay the fee")]
+    InsufficientFeeBalance,
+
+    #[msg("PlaylinkAirdrop: recipient cooldown active")]
+    CooldownActive,
+
+    #[msg("PlaylinkAirdrop: platform does not hold the mint freeze authority")]
+    MissingFreezeAuthority,
+
+    #[msg("PlaylinkAirdrop: platform full")]
+    PlatformFull,
+
+    #[msg("PlaylinkAirdrop: invalid vesting schedule")]
+    InvalidVestingSchedule,
+
+    #[msg("PlaylinkAirdrop: amount not vested yet")]
+    AmountNotVested,
+
+    #[msg("PlaylinkAirdrop: campaign not ended")]
+    CampaignNotEnded,
+
+    #[msg("PlaylinkAirdrop: too many operators")]
+    TooManyOperators,
+
+    #[msg("PlaylinkAirdrop: clock unavailable")]
+    ClockUnavailable,
+
+    #[msg("PlaylinkAirdrop: claim window closed")]
+    ClaimWindowClosed,
+
+    #[msg("PlaylinkAirdrop: invalid creator ATA")]
+    InvalidCreatorAta,
+
+    #[msg("PlaylinkAirdrop: category too long")]
+    CategoryTooLong,
+
+    #[msg("PlaylinkAirdrop: recipient ATA is the creator ATA")]
+    SelfAirdrop,
+
+    #[msg("PlaylinkAirdrop: withdrawal delay can only be raised")]
+    InvalidWithdrawalDelay,
+
+    #[msg("PlaylinkAirdrop: withdrawal timelocked")]
+    WithdrawalTimelocked,
+
+    #[msg("PlaylinkAirdrop: withdrawal already pending")]
+    WithdrawalPending,
+
+    #[msg("PlaylinkAirdrop: no pending withdrawal")]
+    NoPendingWithdrawal,
+
+    #[msg("PlaylinkAirdrop: invalid withdrawal amount")]
+    InvalidWithdrawalAmount,
+
+    #[msg("PlaylinkAirdrop: not a campaign operator")]
+    NotCampaignOperator,
+
+    #[msg("PlaylinkAirdrop: invalid fee bps")]
+    InvalidFeeBps,
+
+    #[msg("PlaylinkAirdrop: invalid fee vault")]
+    InvalidFeeVault,
+
+    #[msg("PlaylinkAirdrop: cannot change mints")]
+    CannotChangeMints,
+
+    #[msg("PlaylinkAirdrop: batch does not resume from the next index")]
+    BatchOutOfOrder,
+
+    #[msg("PlaylinkAirdrop: batch airdrop not allowed with per-recipient rules")]
+    BatchNotAllowed,
+
+    #[msg("PlaylinkAirdrop: campaign has no assets")]
+    NoAssets,
+
+    #[msg("PlaylinkAirdrop: invalid weights")]
+    InvalidWeights,
+
+    #[msg("PlaylinkAirdrop: asset depleted")]
+    AssetDepleted,
+
+    #[msg("PlaylinkAirdrop: campaign creator must co-sign")]
+    CreatorCosignRequired,
+
+    #[msg("PlaylinkAirdrop: campaign duration too short")]
+    DurationTooShort,
+
+    #[msg("PlaylinkAirdrop: invalid vault")]
+    InvalidVault,
+
+    #[msg("PlaylinkAirdrop: campaign assets deposited")]
+    CampaignDeposited,
+
+    #[msg("PlaylinkAirdrop: campaign assets not deposited")]
+    CampaignNotDeposited,
+
+    #[msg("PlaylinkAirdrop: deposit locked while the campaign runs")]
+    DepositLocked,
+
+    #[msg("PlaylinkAirdrop: deposit not withdrawn")]
+    DepositNotWithdrawn,
+
+    #[msg("PlaylinkAirdrop: invalid operator")]
+    InvalidOperator,
+
+    #[msg("PlaylinkAirdrop: recipient does not hold enough tokens")]
+    IneligibleHolder,
+
+    #[msg("PlaylinkAirdrop: mint not allowed")]
+    MintNotAllowed,
+
+    #[msg("PlaylinkAirdrop: asset already partially distributed")]
+    AssetAlreadyDistributed,
+
+    #[msg("PlaylinkAirdrop: airdrop fee exceeds the accepted maximum")]
+    FeeExceedsMax,
+
+    #[msg("PlaylinkAirdrop: cannot merge a campaign into itself")]
+    CannotMergeCampaign,
+
+    #[msg("PlaylinkAirdrop: NFT mint can still be minted")]
+    MintableNftRejected,
+
+    #[msg("PlaylinkAirdrop: campaign fee already withdrawn")]
+    FeeAlreadyWithdrawn,
+
+    #[msg("PlaylinkAirdrop: campaign id too long")]
+    CampaignIdTooLong,
+
+    #[msg("PlaylinkAirdrop: caller is not platform admin")]
+    NotPlatformAdmin,
+}
+
+#[event]
+pub struct PlatformInitialized {
+    admin: Pubkey,
+    fee_per_asset: u64,
 }
 
 #[event]
@@ -419,6 +5250,10 @@ pub struct AirdropCampaignCreated {
     creator: Pubkey,
     assets: Vec<Asset>,
     starting_time: u64,
+    ending_time: u64,
+    metadata_uri: Option<String>,
+    category: Option<String>,
+    campaign_counter: u64,
 }
 
 #[event]
@@ -427,4 +5262,154 @@ pub struct AirdropCampaignUpdated {
     creator: Pubkey,
     assets: Vec<Asset>,
     starting_time: u64,
+    ending_time: u64,
+    metadata_uri: Option<String>,
+    category: Option<String>,
+}
+
+#[event]
+pub struct OperatorsCleared {
+    admin: Pubkey,
+    removed_operators: Vec<Pubkey>,
+}
+
+#[event]
+pub struct AirdropExecuted {
+    campaign_id: String,
+    asset_index: u64,
+    recipient: Pubkey,
+    amount: u64,
+    total_distributed: u64,
+}
+
+#[event]
+pub struct CampaignCreatorChanged {
+    campaign_id: String,
+    previous_creator: Pubkey,
+    new_creator: Pubkey,
+}
+
+#[event]
+pub struct AssetToppedUp {
+    campaign_id: String,
+    asset_index: u64,
+    additional_amount: u64,
+    available_amount: u64,
+}
+
+#[event]
+pub struct CampaignForceClosed {
+    campaign_id: String,
+    closed_by: Pubkey,
+}
+
+#[event]
+pub struct DustSwept {
+    campaign_id: String,
+    amounts: Vec<u64>,
+}
+
+#[event]
+pub struct CampaignsPurged {
+    count: u32,
+}
+
+#[event]
+pub struct CampaignSplit {
+    source_id: String,
+    new_id: String,
+    creator: Pubkey,
+    moved_assets: u64,
+}
+
+#[event]
+pub struct CampaignsMerged {
+    target_id: String,
+    source_id: String,
+    creator: Pubkey,
+    refunded_fee: u64,
+}
+
+#[event]
+pub struct CampaignCancelled {
+    campaign_id: String,
+    creator: Pubkey,
+    refunded_fee: u64,
+}
+
+#[event]
+pub struct CampaignReclaimed {
+    campaign_id: String,
+    creator: Pubkey,
+    reclaimed_amount: u64,
+}
+
+#[event]
+pub struct AssetsDeposited {
+    campaign_id: String,
+    creator: Pubkey,
+}
+
+#[event]
+pub struct DepositWithdrawn {
+    campaign_id: String,
+    asset_index: u64,
+    amount: u64,
+}
+
+#[event]
+pub struct BatchAirdropExecuted {
+    campaign_id: String,
+    asset_index: u64,
+    start_index: u64,
+    recipients: u64,
+    amount: u64,
+    total_distributed: u64,
+}
+
+#[event]
+pub struct BatchAirdropCompleted {
+    campaign_id: String,
+    recipient_count: u64,
+    total_amount: u64,
+}
+
+#[event]
+pub struct RecipientsUploaded {
+    campaign_id: String,
+    chunk_index: u32,
+    recipients: u64,
+}
+
+#[event]
+pub struct RecipientChunkDistributed {
+    campaign_id: String,
+    chunk_index: u32,
+    recipients: u64,
+    amount: u64,
+    total_distributed: u64,
+}
+
+#[event]
+pub struct StandardAirdropEvent {
+    program_id: Pubkey,
+    campaign: String,
+    recipient: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+    timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_timestamp_fails_cleanly_without_clock() {
+        // Off-chain, the clock sysvar cannot be read
+        assert_eq!(
+            ProgramError::from(current_timestamp().unwrap_err()),
+            ProgramError::from(Error::from(PlaylinkAirdropErr::ClockUnavailable))
+        );
+    }
 }
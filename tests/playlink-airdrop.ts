@@ -1,16 +1,21 @@
 import * as anchor from "@project-serum/anchor";
 import { Program } from "@project-serum/anchor";
 import {
+  ACCOUNT_SIZE,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   createApproveInstruction,
+  AuthorityType,
   createAssociatedTokenAccountInstruction,
+  createInitializeAccountInstruction,
   createInitializeMintInstruction,
   createMintToInstruction,
+  createSetAuthorityInstruction,
   getAccount,
   getAssociatedTokenAddress,
   MINT_SIZE,
   TOKEN_PROGRAM_ID
 } from '@solana/spl-token';
-import { SYSVAR_CLOCK_PUBKEY, ParsedAccountData, PublicKey } from "@solana/web3.js";
+import { ComputeBudgetProgram, SYSVAR_CLOCK_PUBKEY, ParsedAccountData, PublicKey } from "@solana/web3.js";
 import { expect } from 'chai';
 import { PlaylinkAirdrop } from '../target/types/playlink_airdrop';
 
@@ -29,8 +34,102 @@ describe("Playlink Airdrop program", () => {
   const campaignCreator = anchor.web3.Keypair.generate();
   const participant = anchor.web3.Keypair.generate(); // campaign participant
   const recipient = anchor.web3.Keypair.generate(); // Airdrop fee recipient
+  const MIN_START_DELAY = 60; // Campaigns cannot start sooner than this after being scheduled
   let airdropPlatform: PublicKey = null;
 
+  let getNow = async (): Promise<number> => {
+    return ((await connection.getParsedAccountInfo(SYSVAR_CLOCK_PUBKEY)).value!.data as ParsedAccountData).parsed?.info?.unixTimestamp;
+  };
+
+  // Wait until the on-chain clock reaches `timestamp`
+  let waitUntil = async (timestamp: number) => {
+    while ((await getNow()) < timestamp) {
+      await sleep(1);
+    }
+  };
+
+  // Simulate a view instruction and return the raw data it sets via `set_return_data`
+  let viewReturnData = async (ix: anchor.web3.TransactionInstruction): Promise<Buffer> => {
+    let tx = new anchor.web3.Transaction().add(ix);
+    tx.feePayer = defaultWallet.publicKey;
+    tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+    let result = await connection.simulateTransaction(await defaultWallet.signTransaction(tx));
+    // The runtime trims trailing zeros from return data, pad them back for the decoders
+    let data = Buffer.from(result.value.returnData?.data[0] ?? "", "base64");
+    return Buffer.concat([data, Buffer.alloc(64)]);
+  };
+
+  // Fees of the campaigns that are neither completed nor ended, which creators may still get back
+  let refundableFees = async (): Promise<number> => {
+    let now = await getNow();
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    return campaigns
+      .filter(c => !c.completed && (c.endingTime.toNumber() == 0 || now <= c.endingTime.toNumber()))
+      .reduce((total, c) => total + c.airdropFee.toNumber(), 0);
+  };
+
+  // Parse the events emitted by a confirmed transaction from its logs
+  let transactionEvents = async (signature: string): Promise<anchor.Event[]> => {
+    let transaction = await connection.getTransaction(signature, { commitment: "confirmed" });
+    let events: anchor.Event[] = [];
+    new anchor.EventParser(program.programId, program.coder).parseLogs(transaction.meta.logMessages, event => events.push(event));
+    return events;
+  };
+
+  // Every platform lives at its own address, derived from its id
+  let platformAddress = async (platformId: string): Promise<PublicKey> => {
+    let [platform] = await PublicKey.findProgramAddress(
+      [anchor.utils.bytes.utf8.encode("airdrop_platform"), anchor.utils.bytes.utf8.encode(platformId)],
+      program.programId
+    );
+    return platform;
+  };
+
+  // Every recipient has a receipt per campaign, tracking what they received from it
+  let receiptAddress = async (
+    campaignId: string,
+    owner: PublicKey,
+    creator: PublicKey = campaignCreator.publicKey,
+    platform: PublicKey = airdropPlatform
+  ): Promise<PublicKey> => {
+    let [receipt] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("receipt"),
+        platform.toBuffer(),
+        creator.toBuffer(),
+        anchor.utils.bytes.utf8.encode(campaignId),
+        owner.toBuffer()
+      ],
+      program.programId
+    );
+    return receipt;
+  };
+
+  // Campaign instructions expect the mint of every token asset, in the same order as the assets
+  let assetMints = (assets: any[]) => assets
+    .filter(asset => asset.kind.token)
+    .map(asset => ({ pubkey: asset.kind.token.mint, isWritable: false, isSigner: false }));
+
+  // Create a new mint and mint `amount` tokens into the owner's associated token account
+  let createMint = async (decimals: number, amount: number, owner: PublicKey): Promise<PublicKey> => {
+    let mint = anchor.web3.Keypair.generate();
+    let ownerATA = await getAssociatedTokenAddress(mint.publicKey, owner);
+    let tx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: defaultWallet.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space: MINT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+        lamports: await connection.getMinimumBalanceForRentExemption(MINT_SIZE)
+      }),
+      createInitializeMintInstruction(mint.publicKey, decimals, defaultWallet.publicKey, defaultWallet.publicKey),
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, ownerATA, owner, mint.publicKey),
+      createMintToInstruction(mint.publicKey, ownerATA, defaultWallet.publicKey, amount)
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+  };
+
   it("Initialize new accounts", async () => {
     let initTx = new anchor.web3.Transaction().add(
       ...[
@@ -42,7 +141,7 @@ describe("Playlink Airdrop program", () => {
       ].map(account => anchor.web3.SystemProgram.transfer({
         fromPubkey: defaultWallet.publicKey,
         toPubkey: account,
-        lamports: 1000000000
+        lamports: 100000000000
       }))
     );
     await provider.sendAndConfirm(initTx);
@@ -108,14 +207,102 @@ describe("Playlink Airdrop program", () => {
   });
 
   it("Initialize PlaylinkAirdrop platform", async () => {
-    [airdropPlatform] = await PublicKey.findProgramAddress([anchor.utils.bytes.utf8.encode("airdrop_platform")], program.programId);
-    await program.methods.initialize(new anchor.BN(700000000)).accounts({
+    airdropPlatform = await platformAddress("");
+    let signature = await program.methods.initialize(
+      "",
+      new anchor.BN(700000000),
+      new anchor.BN(10000000),
+      new anchor.BN(1000000000),
+      0
+    ).accounts({
       airdropPlatform,
       admin: admin.publicKey,
+      payer: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc({ commitment: "confirmed" });
+    let events = await transactionEvents(signature);
+    expect(events.length).to.equal(1);
+    expect(events[0].name).to.equal("PlatformInitialized");
+    expect(events[0].data.admin.toString()).to.equal(admin.publicKey.toString());
+    expect(events[0].data.feePerAsset.toString()).to.equal("700000000");
+    let platform = await program.account.airdropPlatform.fetch(airdropPlatform);
+    expect(platform.feePerAsset.toString()).to.equal("700000000");
+    expect(platform.minFeePerAsset.toString()).to.equal("10000000");
+    expect(platform.maxFeePerAsset.toString()).to.equal("1000000000");
+  });
+
+  it("Migrate platform idempotently", async () => {
+    let migratePlatform = () => program.methods.migratePlatform().accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
       systemProgram: anchor.web3.SystemProgram.programId
     }).signers([admin]).rpc();
-    let currentFeePerAsset = (await program.account.airdropPlatform.fetch(airdropPlatform)).feePerAsset;
-    expect(currentFeePerAsset.toString()).to.equal("700000000");
+    let snapshot = async () => {
+      let accountInfo = await connection.getAccountInfo(airdropPlatform);
+      let platform = await program.account.airdropPlatform.fetch(airdropPlatform);
+      return [accountInfo.data.length, accountInfo.lamports, platform.schemaVersion, await connection.getBalance(admin.publicKey)];
+    };
+    await migratePlatform();
+    let afterFirstMigration = await snapshot();
+    expect(afterFirstMigration[0]).to.equal(10240);
+    expect(afterFirstMigration[2]).to.equal(3);
+    await migratePlatform();
+    expect(await snapshot()).to.deep.equal(afterFirstMigration);
+  });
+
+  it("Migrate a platform from the legacy layout", async () => {
+    // Loaded by the test validator from tests/fixtures, see Anchor.toml
+    let legacyPlatform = new PublicKey("8kK4bxGMMugCPRDx64PHeMinzxbEAMfR8c7aNaivEo9T");
+    let legacyMint = new PublicKey("EFy7qbCd7VefezPWU1aZibjQFxtaS8jnqp3YhsNTuXPJ");
+    let legacyAdmin = anchor.web3.Keypair.fromSeed(new Uint8Array(32).fill(7));
+    let creators = ["9zTpibgVB7WUVAZWZAXw2kJujA9tg42azYrfAUkXSikt", "A1PGAdHrKF9vw6KrK8rfLJC97yoHWKqbgpvzZ22nFrDM"];
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(anchor.web3.SystemProgram.transfer({
+      fromPubkey: defaultWallet.publicKey,
+      toPubkey: legacyAdmin.publicKey,
+      lamports: 1000000000
+    })));
+    let migratePlatform = (signer: anchor.web3.Keypair) => program.methods.migratePlatform().accounts({
+      admin: signer.publicKey,
+      airdropPlatform: legacyPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts([{ pubkey: legacyMint, isSigner: false, isWritable: false }]).signers([signer]).rpc();
+
+    expect((await connection.getAccountInfo(legacyPlatform)).data.length).to.equal(9000);
+    await expectError(migratePlatform(admin), "NotPlatformAdmin");
+    await migratePlatform(legacyAdmin);
+
+    // Fees were every lamport above rent, the admin paid for the extra rent
+    let accountInfo = await connection.getAccountInfo(legacyPlatform);
+    expect(accountInfo.data.length).to.equal(10240);
+    expect(accountInfo.lamports).to.equal(await connection.getMinimumBalanceForRentExemption(10240) + 2100000000);
+    let platform = await program.account.airdropPlatform.fetch(legacyPlatform);
+    expect(platform.schemaVersion).to.equal(3);
+    expect(platform.admin.toString()).to.equal(legacyAdmin.publicKey.toString());
+    expect(platform.operators.map(op => op.toString())).to.deep.equal([legacyAdmin.publicKey.toString()]);
+    expect(platform.feePerAsset.toString()).to.equal("700000000");
+    expect(platform.minFeePerAsset.toString()).to.equal("700000000");
+    expect(platform.maxFeePerAsset.toString()).to.equal("700000000");
+    expect(platform.accumulatedFees.toString()).to.equal("2100000000");
+    expect(platform.platformId).to.equal("");
+
+    // Campaigns are sorted by creator and id, their assets are tokens with the decimals of their mint
+    let campaigns: any = platform.allCampaigns;
+    expect(campaigns.map(c => [c.creator.toString(), c.campaignId])).to.deep.equal([
+      [creators[0], "LEGACY-1"],
+      [creators[1], "LEGACY-2"]
+    ]);
+    expect(campaigns[0].assets.map(a => [a.kind.token.mint.toString(), a.availableAmount.toString(), a.decimals, a.isNft]))
+      .to.deep.equal([[legacyMint.toString(), "300", 6, false], [legacyMint.toString(), "200", 6, false]]);
+    expect(campaigns[0].totalAvailableAssets.toString()).to.equal("500");
+    expect(campaigns[0].airdropFee.toString()).to.equal("1400000000");
+    expect(campaigns[0].feePerAsset.toString()).to.equal("700000000");
+    expect(campaigns[0].startingTime.toString()).to.equal("1700000000");
+    expect(campaigns[0].endingTime.toString()).to.equal("0");
+    expect(campaigns[1].assets[0].availableAmount.toString()).to.equal("500");
+
+    // Migrating again changes nothing
+    await migratePlatform(legacyAdmin);
+    expect((await connection.getAccountInfo(legacyPlatform)).data).to.deep.equal(accountInfo.data);
   });
 
   it("Set operator", async () => {
@@ -140,33 +327,82 @@ describe("Playlink Airdrop program", () => {
     expect(currentFeePerAsset.toString()).to.equal("100000000");
   });
 
+  it("Reject fee per asset outside of the configured bounds", async () => {
+    for (let fee of [9999999, 1000000001]) {
+      await expectError(
+        program.methods.setFeePerAsset(new anchor.BN(fee)).accounts({
+          operator: operator.publicKey,
+          airdropPlatform,
+          systemProgram: anchor.web3.SystemProgram.programId
+        }).signers([operator]).rpc(),
+        "FeeOutOfBounds"
+      );
+    }
+    let currentFeePerAsset = (await program.account.airdropPlatform.fetch(airdropPlatform)).feePerAsset;
+    expect(currentFeePerAsset.toString()).to.equal("100000000");
+  });
+
+  it("Admin updates fee per asset without being an operator", async () => {
+    let setAdminAsOperator = (isOperator: boolean) => program.methods.setOperators([admin.publicKey], [isOperator]).accounts({
+      airdropPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let setFeePerAsset = (signer: anchor.web3.Keypair, fee: number) => program.methods.setFeePerAsset(new anchor.BN(fee)).accounts({
+      operator: signer.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([signer]).rpc();
+
+    await setAdminAsOperator(false);
+    let operators = (await program.account.airdropPlatform.fetch(airdropPlatform)).operators;
+    expect(operators.some(op => op.toString() == admin.publicKey.toString())).to.equal(false);
+    await setFeePerAsset(admin, 200000000);
+    expect((await program.account.airdropPlatform.fetch(airdropPlatform)).feePerAsset.toString()).to.equal("200000000");
+    await expectError(setFeePerAsset(participant, 100000000), "ConstraintRaw");
+
+    // Restore the fee and the admin operator for the following tests
+    await setFeePerAsset(operator, 100000000);
+    await setAdminAsOperator(true);
+  });
+
   it("Create airdrop campaign", async () => {
     let now = ((await connection.getParsedAccountInfo(SYSVAR_CLOCK_PUBKEY)).value!.data as ParsedAccountData).parsed?.info?.unixTimestamp;
     let platformBalanceBefore = await connection.getBalance(airdropPlatform);
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(34000000000),
+      isNft: false,
+      decimals: 9
+    }, {
+      kind: { token: { mint: token2.publicKey } },
+      availableAmount: new anchor.BN(90),
+      isNft: false,
+      decimals: 0
+    }];
     await program.methods.createAirdropCampaign(
       "01BX5ZZKBKACTAV9WEVGEMMVRY",
-      [{
-        assetAddress: token1.publicKey,
-        availableAmount: new anchor.BN(34000000000)
-      }, {
-        assetAddress: token2.publicKey,
-        availableAmount: new anchor.BN(90)
-      }],
-      new anchor.BN(now + 30 * 60)
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
     ).accounts({
       airdropPlatform,
       campaignCreator: campaignCreator.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId
-    }).signers([campaignCreator]).rpc();
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
     let campaigns: any = await (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
     let platformBalanceAfter = await connection.getBalance(airdropPlatform);
     expect(campaigns?.length).to.equal(1);
     expect(campaigns[0]?.campaignId).to.equal("01BX5ZZKBKACTAV9WEVGEMMVRY");
     expect(campaigns[0]?.creator?.toString()).to.equal(campaignCreator.publicKey.toString());
     expect(campaigns[0]?.assets?.length).to.equal(2);
-    expect(campaigns[0]?.assets[0]?.assetAddress?.toString()).to.equal(token1.publicKey.toString());
+    expect(campaigns[0]?.assets[0]?.kind?.token?.mint?.toString()).to.equal(token1.publicKey.toString());
     expect(campaigns[0]?.assets[0]?.availableAmount?.toString()).to.equal("34000000000");
-    expect(campaigns[0]?.assets[1]?.assetAddress?.toString()).to.equal(token2.publicKey.toString());
+    expect(campaigns[0]?.assets[1]?.kind?.token?.mint?.toString()).to.equal(token2.publicKey.toString());
     expect(campaigns[0]?.assets[1]?.availableAmount?.toString()).to.equal("90");
     expect(campaigns[0]?.startingTime?.toString()).to.equal((now + 30 * 60).toString());
     expect(campaigns[0]?.totalAvailableAssets?.toString()).to.equal("34000000090");
@@ -203,40 +439,51 @@ describe("Playlink Airdrop program", () => {
   it("Update airdrop campaign", async () => {
     let now = ((await connection.getParsedAccountInfo(SYSVAR_CLOCK_PUBKEY)).value!.data as ParsedAccountData).parsed?.info?.unixTimestamp;
     let platformBalanceBefore = await connection.getBalance(airdropPlatform);
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(31000000000),
+      isNft: false,
+      decimals: 9
+    }, {
+      kind: { token: { mint: token2.publicKey } },
+      availableAmount: new anchor.BN(88),
+      isNft: false,
+      decimals: 0
+    }, {
+      kind: { token: { mint: token2.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
     await program.methods.updateCampaign(
       "01BX5ZZKBKACTAV9WEVGEMMVRY",
-      [{
-        assetAddress: token1.publicKey,
-        availableAmount: new anchor.BN(31000000000)
-      }, {
-        assetAddress: token2.publicKey,
-        availableAmount: new anchor.BN(88)
-      }, {
-        assetAddress: token2.publicKey,
-        availableAmount: new anchor.BN(1)
-      }],
-      new anchor.BN(now + 8)
+      assets,
+      new anchor.BN(now + MIN_START_DELAY + 8),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
     ).accounts({
       airdropPlatform,
       campaignCreator: campaignCreator.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId
-    }).signers([campaignCreator]).rpc();
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
     let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
     let platformBalanceAfter = await connection.getBalance(airdropPlatform);
     expect(campaigns?.length).to.equal(1);
     expect(campaigns[0]?.assets?.length).to.equal(3);
-    expect(campaigns[0]?.assets[0]?.assetAddress?.toString()).to.equal(token1.publicKey.toString());
+    expect(campaigns[0]?.assets[0]?.kind?.token?.mint?.toString()).to.equal(token1.publicKey.toString());
     expect(campaigns[0]?.assets[0]?.availableAmount?.toString()).to.equal("31000000000");
-    expect(campaigns[0]?.assets[1]?.assetAddress?.toString()).to.equal(token2.publicKey.toString());
+    expect(campaigns[0]?.assets[1]?.kind?.token?.mint?.toString()).to.equal(token2.publicKey.toString());
     expect(campaigns[0]?.assets[1]?.availableAmount?.toString()).to.equal("88");
-    expect(campaigns[0]?.assets[2]?.assetAddress?.toString()).to.equal(token2.publicKey.toString());
+    expect(campaigns[0]?.assets[2]?.kind?.token?.mint?.toString()).to.equal(token2.publicKey.toString());
     expect(campaigns[0]?.assets[2]?.availableAmount?.toString()).to.equal("1");
     expect(campaigns[0]?.totalAvailableAssets?.toString()).to.equal("31000000089");
     expect((platformBalanceAfter - platformBalanceBefore).toString()).to.equal("100000000");
   });
 
   it("Airdrop", async () => {
-    await sleep(10); // Wait until this campaign starts
+    await sleep(MIN_START_DELAY + 10); // Wait until this campaign starts
     let creatorATA1 = await getAssociatedTokenAddress(token1.publicKey, campaignCreator.publicKey);
     let creatorATA2 = await getAssociatedTokenAddress(token2.publicKey, campaignCreator.publicKey);
     let participantATA1 = await getAssociatedTokenAddress(token1.publicKey, participant.publicKey);
@@ -251,11 +498,15 @@ describe("Playlink Airdrop program", () => {
       await program.methods.airdrop("01BX5ZZKBKACTAV9WEVGEMMVRY", new anchor.BN(0)).accounts({
         creatorAta: creatorATA1,
         recipientAta: participantATA1,
+        recipient: participant.publicKey,
         mint: token1.publicKey,
         campaignCreator: campaignCreator.publicKey,
         operator: operator.publicKey,
+        receipt: await receiptAddress("01BX5ZZKBKACTAV9WEVGEMMVRY", participant.publicKey),
         airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId
       }).instruction(),
       createAssociatedTokenAccountInstruction(
@@ -267,21 +518,29 @@ describe("Playlink Airdrop program", () => {
       await program.methods.airdrop("01BX5ZZKBKACTAV9WEVGEMMVRY", new anchor.BN(1)).accounts({
         creatorAta: creatorATA2,
         recipientAta: participantATA2,
+        recipient: participant.publicKey,
         mint: token2.publicKey,
         campaignCreator: campaignCreator.publicKey,
         operator: operator.publicKey,
+        receipt: await receiptAddress("01BX5ZZKBKACTAV9WEVGEMMVRY", participant.publicKey),
         airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId
       }).instruction(),
       await program.methods.airdrop("01BX5ZZKBKACTAV9WEVGEMMVRY", new anchor.BN(2)).accounts({
         creatorAta: creatorATA2,
         recipientAta: participantATA2,
+        recipient: participant.publicKey,
         mint: token2.publicKey,
         campaignCreator: campaignCreator.publicKey,
         operator: operator.publicKey,
+        receipt: await receiptAddress("01BX5ZZKBKACTAV9WEVGEMMVRY", participant.publicKey),
         airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId
       }).instruction()
     );
@@ -300,24 +559,36 @@ describe("Playlink Airdrop program", () => {
 
   it("Create another campaign", async () => {
     let now = ((await connection.getParsedAccountInfo(SYSVAR_CLOCK_PUBKEY)).value!.data as ParsedAccountData).parsed?.info?.unixTimestamp;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1234000),
+      isNft: false,
+      decimals: 9
+    }, {
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(4321000),
+      isNft: false,
+      decimals: 9
+    }, {
+      kind: { token: { mint: token2.publicKey } },
+      availableAmount: new anchor.BN(22),
+      isNft: false,
+      decimals: 0
+    }];
     await program.methods.createAirdropCampaign(
       "01BX5ZZKBKACTAV9WEVGEMMVRK",
-      [{
-        assetAddress: token1.publicKey,
-        availableAmount: new anchor.BN(1234000)
-      }, {
-        assetAddress: token1.publicKey,
-        availableAmount: new anchor.BN(4321000)
-      }, {
-        assetAddress: token2.publicKey,
-        availableAmount: new anchor.BN(22)
-      }],
-      new anchor.BN(now + 2)
+      assets,
+      new anchor.BN(now + MIN_START_DELAY + 2),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
     ).accounts({
       airdropPlatform,
       campaignCreator: campaignCreator.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId
-    }).signers([campaignCreator]).rpc();
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
     let campaigns: any = await (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
     expect(campaigns.length).to.equal(1);
     expect(campaigns[0]?.totalAvailableAssets?.toString()).to.equal("5555022");
@@ -350,7 +621,7 @@ describe("Playlink Airdrop program", () => {
   });
 
   it("Airdrop", async () => {
-    await sleep(3);
+    await sleep(MIN_START_DELAY + 3);
     let creatorATA1 = await getAssociatedTokenAddress(token1.publicKey, campaignCreator.publicKey);
     let creatorATA2 = await getAssociatedTokenAddress(token2.publicKey, campaignCreator.publicKey);
     let participantATA1 = await getAssociatedTokenAddress(token1.publicKey, participant.publicKey);
@@ -359,21 +630,29 @@ describe("Playlink Airdrop program", () => {
       await program.methods.airdrop("01BX5ZZKBKACTAV9WEVGEMMVRK", new anchor.BN(0)).accounts({
         creatorAta: creatorATA1,
         recipientAta: participantATA1,
+        recipient: participant.publicKey,
         mint: token1.publicKey,
         campaignCreator: campaignCreator.publicKey,
         operator: operator.publicKey,
+        receipt: await receiptAddress("01BX5ZZKBKACTAV9WEVGEMMVRK", participant.publicKey),
         airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId
       }).instruction(),
       await program.methods.airdrop("01BX5ZZKBKACTAV9WEVGEMMVRK", new anchor.BN(2)).accounts({
         creatorAta: creatorATA2,
         recipientAta: participantATA2,
+        recipient: participant.publicKey,
         mint: token2.publicKey,
         campaignCreator: campaignCreator.publicKey,
         operator: operator.publicKey,
+        receipt: await receiptAddress("01BX5ZZKBKACTAV9WEVGEMMVRK", participant.publicKey),
         airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId
       }).instruction()
     );
@@ -383,18 +662,5321 @@ describe("Playlink Airdrop program", () => {
     expect(campaigns[0]?.totalAvailableAssets?.toString()).to.equal("4321000");
   });
 
-  it("Admin withdraws airdrop fee", async () => {
-    await program.methods.withdrawAirdropFee().accounts({
-      recipient: recipient.publicKey,
+  it("Airdrop fails with a clear error when creator balance is insufficient", async () => {
+    let now = ((await connection.getParsedAccountInfo(SYSVAR_CLOCK_PUBKEY)).value!.data as ParsedAccountData).parsed?.info?.unixTimestamp;
+    let creatorATA2 = await getAssociatedTokenAddress(token2.publicKey, campaignCreator.publicKey);
+    let participantATA2 = await getAssociatedTokenAddress(token2.publicKey, participant.publicKey);
+    let assets: any[] = [{
+      kind: { token: { mint: token2.publicKey } },
+      availableAmount: new anchor.BN(100000),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMVRZ",
+      assets,
+      new anchor.BN(now + MIN_START_DELAY + 2),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA2, airdropPlatform, campaignCreator.publicKey, 100000)
+    ), [campaignCreator]);
+    await sleep(MIN_START_DELAY + 3);
+    await expectError(
+      program.methods.airdrop("01BX5ZZKBKACTAV9WEVGEMMVRZ", new anchor.BN(0)).accounts({
+        creatorAta: creatorATA2,
+        recipientAta: participantATA2,
+        recipient: participant.publicKey,
+        mint: token2.publicKey,
+        campaignCreator: campaignCreator.publicKey,
+        operator: operator.publicKey,
+        receipt: await receiptAddress("01BX5ZZKBKACTAV9WEVGEMMVRZ", participant.publicKey),
+        airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([operator]).rpc(),
+      "InsufficientCreatorBalance"
+    );
+  });
+
+  it("Operator creates a fee-free campaign", async () => {
+    let now = ((await connection.getParsedAccountInfo(SYSVAR_CLOCK_PUBKEY)).value!.data as ParsedAccountData).parsed?.info?.unixTimestamp;
+    let platformBalanceBefore = await connection.getBalance(airdropPlatform);
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaignWaived(
+      "01BX5ZZKBKACTAV9WEVGEMMVW1",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      operator: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([operator]).rpc();
+    let platformBalanceAfter = await connection.getBalance(airdropPlatform);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMVW1");
+    expect(campaign?.creator?.toString()).to.equal(operator.publicKey.toString());
+    expect(campaign?.airdropFee?.toString()).to.equal("0");
+    expect(platformBalanceAfter).to.equal(platformBalanceBefore);
+
+    await expectError(
+      program.methods.createAirdropCampaignWaived(
+        "01BX5ZZKBKACTAV9WEVGEMMVW2",
+        assets,
+        new anchor.BN(now + 30 * 60),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0)
+      ).accounts({
+        airdropPlatform,
+        operator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc(),
+      "ConstraintRaw"
+    );
+  });
+
+  it("Create NFT campaign with a proper NFT mint", async () => {
+    let now = ((await connection.getParsedAccountInfo(SYSVAR_CLOCK_PUBKEY)).value!.data as ParsedAccountData).parsed?.info?.unixTimestamp;
+    let nft = await createMint(0, 1, campaignCreator.publicKey);
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createSetAuthorityInstruction(nft, defaultWallet.publicKey, AuthorityType.MintTokens, null)
+    ), []);
+    let assets: any[] = [{
+      kind: { token: { mint: nft } },
+      availableAmount: new anchor.BN(1),
+      isNft: true,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMNF1",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMNF1");
+    expect(campaign?.assets[0]?.isNft).to.equal(true);
+  });
+
+  it("Reject NFT campaign with a fungible mint", async () => {
+    let now = ((await connection.getParsedAccountInfo(SYSVAR_CLOCK_PUBKEY)).value!.data as ParsedAccountData).parsed?.info?.unixTimestamp;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: true,
+      decimals: 9
+    }];
+    await expectError(
+      program.methods.createAirdropCampaign(
+        "01BX5ZZKBKACTAV9WEVGEMMNF2",
+        assets,
+        new anchor.BN(now + 30 * 60),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc(),
+      "InvalidNftAsset"
+    );
+  });
+
+  it("Campaign lookup scales to many campaigns", async () => {
+    // A fresh platform keeps the benchmark independent from the campaigns left over by earlier tests.
+    // It outgrows PLATFORM_SPACE on the way and the program reallocs it, while (de)serializing
+    // 50 campaigns needs a bigger heap than the default 32 KiB
+    let benchPlatform = await platformAddress("bench");
+    let heapFrame = [ComputeBudgetProgram.requestHeapFrame({ bytes: 256 * 1024 })];
+    await program.methods.initialize(
+      "bench",
+      new anchor.BN(0),
+      new anchor.BN(0),
+      new anchor.BN(1000000000),
+      0
+    ).accounts({
+      airdropPlatform: benchPlatform,
+      admin: admin.publicKey,
+      payer: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    await program.methods.setOperators([operator.publicKey], [true]).accounts({
+      airdropPlatform: benchPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+
+    let benchToken = await createMint(0, 50, operator.publicKey);
+    let operatorATA = await getAssociatedTokenAddress(benchToken, operator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(benchToken, participant.publicKey);
+    let campaignIds = [...Array(50).keys()].map(i => `BENCH-${(49 - i).toString().padStart(2, "0")}`);
+    let startingTime = (await getNow()) + 120;
+    for (let campaignId of campaignIds) {
+      let assets: any[] = [{
+        kind: { token: { mint: benchToken } },
+        availableAmount: new anchor.BN(1),
+        isNft: false,
+        decimals: 0
+      }];
+      await program.methods.createAirdropCampaignWaived(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0)
+      ).accounts({
+        airdropPlatform: benchPlatform,
+        operator: operator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).preInstructions(heapFrame).signers([operator]).rpc();
+    }
+    let campaigns: any = (await program.account.airdropPlatform.fetch(benchPlatform)).allCampaigns;
+    let storedIds = campaigns.map(c => c.campaignId);
+    expect(storedIds).to.deep.equal([...campaignIds].sort());
+    expect((await connection.getAccountInfo(benchPlatform)).data.length).to.be.greaterThan(10240);
+
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(operator.publicKey, participantATA, participant.publicKey, benchToken),
+      createApproveInstruction(operatorATA, benchPlatform, operator.publicKey, 50)
+    ), [operator]);
+    await waitUntil(startingTime);
+
+    // Drain every campaign so that they do not linger in the platform account
+    let signature: string;
+    for (let campaignId of [...campaignIds].sort()) {
+      signature = await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+        creatorAta: operatorATA,
+        recipientAta: participantATA,
+        recipient: participant.publicKey,
+        mint: benchToken,
+        campaignCreator: operator.publicKey,
+        operator: operator.publicKey,
+        receipt: await receiptAddress(campaignId, participant.publicKey, operator.publicKey, benchPlatform),
+        airdropPlatform: benchPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).preInstructions(heapFrame).signers([operator]).rpc({ commitment: "confirmed" });
+    }
+    let lastAirdrop = await connection.getTransaction(signature, { commitment: "confirmed" });
+    expect(lastAirdrop.meta.computeUnitsConsumed).to.be.lessThan(100000);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("50");
+    campaigns = (await program.account.airdropPlatform.fetch(benchPlatform)).allCampaigns;
+    expect(campaigns.length).to.equal(0);
+  });
+
+  it("Reschedule campaign without touching its assets", async () => {
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(5000),
+      isNft: false,
+      decimals: 9
+    }, {
+      kind: { token: { mint: token2.publicKey } },
+      availableAmount: new anchor.BN(7),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMRS1",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await program.methods.rescheduleCampaign("01BX5ZZKBKACTAV9WEVGEMMRS1", new anchor.BN(now + 60 * 60)).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMRS1");
+    expect(campaign?.startingTime?.toString()).to.equal((now + 60 * 60).toString());
+    expect(campaign?.assets?.length).to.equal(2);
+    expect(campaign?.assets[0]?.availableAmount?.toString()).to.equal("5000");
+    expect(campaign?.assets[1]?.availableAmount?.toString()).to.equal("7");
+    expect(campaign?.totalAvailableAssets?.toString()).to.equal("5007");
+  });
+
+  it("Reject campaign whose declared decimals mismatch the mint", async () => {
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 6
+    }];
+    await expectError(
+      program.methods.createAirdropCampaign(
+        "01BX5ZZKBKACTAV9WEVGEMMDC1",
+        assets,
+        new anchor.BN(now + 30 * 60),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc(),
+      "DecimalsMismatch"
+    );
+  });
+
+  it("Reject campaign starting too far in the future", async () => {
+    let now = await getNow();
+    let twoYearsLater = now + 2 * 365 * 24 * 60 * 60;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await expectError(
+      program.methods.createAirdropCampaign(
+        "01BX5ZZKBKACTAV9WEVGEMMFAR",
+        assets,
+        new anchor.BN(twoYearsLater),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc(),
+      "ScheduleTooFar"
+    );
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMFAR",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await expectError(
+      program.methods.updateCampaign(
+        "01BX5ZZKBKACTAV9WEVGEMMFAR",
+        assets,
+        new anchor.BN(twoYearsLater),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0)
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc(),
+      "ScheduleTooFar"
+    );
+  });
+
+  it("Reject campaign whose asset amounts overflow", async () => {
+    let now = await getNow();
+    let assets: any[] = ["18446744073709551615", "1"].map(amount => ({
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 9
+    }));
+    await expectError(
+      program.methods.createAirdropCampaign(
+        "01BX5ZZKBKACTAV9WEVGEMMOVF",
+        assets,
+        new anchor.BN(now + 30 * 60),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc(),
+      "ArithmeticOverflow"
+    );
+  });
+
+  it("Refund excess fee when an update reduces the asset count", async () => {
+    let now = await getNow();
+    let assets: any[] = [1000, 2000, 3000].map(amount => ({
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 9
+    }));
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMRF1",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let creatorBalanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    assets = assets.slice(0, 1);
+    await program.methods.updateCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMRF1",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let creatorBalanceAfter = await connection.getBalance(campaignCreator.publicKey);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMRF1");
+    expect(campaign?.airdropFee?.toString()).to.equal("100000000");
+    expect((creatorBalanceAfter - creatorBalanceBefore).toString()).to.equal("200000000");
+  });
+
+  it("Query fees available for withdrawal", async () => {
+    let withdrawableFees = async () => new anchor.BN(
+      await viewReturnData(await program.methods.withdrawableFees().accounts({ airdropPlatform }).instruction()),
+      "le"
+    );
+    let rentExemptMinimum = await connection.getMinimumBalanceForRentExemption(10240);
+    let now = await getNow();
+    let before = await withdrawableFees();
+    expect(before.toString()).to.equal(((await connection.getBalance(airdropPlatform)) - rentExemptMinimum - (await refundableFees())).toString());
+
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMWF1",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    // The fee of a campaign that can still be refunded is not withdrawable yet
+    let after = await withdrawableFees();
+    expect(after.sub(before).toString()).to.equal("0");
+  });
+
+  it("Claim with an operator-signed authorization", async () => {
+    let claimToken = await createMint(0, 100, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(claimToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(claimToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMCL1";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: claimToken } },
+      availableAmount: new anchor.BN(100),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(participant.publicKey, participantATA, participant.publicKey, claimToken),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 100)
+    ), [participant, campaignCreator]);
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let claim = async (signer: anchor.web3.Keypair, amount: number, expiry: number, platform = airdropPlatform) => {
+      let message = Buffer.concat([
+        program.programId.toBuffer(),
+        platform.toBuffer(),
+        campaignCreator.publicKey.toBuffer(),
+        Buffer.from(campaignId),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+        participant.publicKey.toBuffer(),
+        new anchor.BN(amount).toArrayLike(Buffer, "le", 8),
+        new anchor.BN(expiry).toArrayLike(Buffer, "le", 8)
+      ]);
+      let verifyIx = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message });
+      let signature = Array.from(verifyIx.data.slice(48, 112));
+      return program.methods.claimWithAuthorization(
+        campaignId,
+        new anchor.BN(0),
+        new anchor.BN(amount),
+        new anchor.BN(expiry),
+        signature
+      ).accounts({
+        creatorAta: creatorATA,
+        recipientAta: participantATA,
+        mint: claimToken,
+        campaignCreator: campaignCreator.publicKey,
+        recipient: participant.publicKey,
+        receipt,
+        airdropPlatform,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).preInstructions([verifyIx]).signers([participant]).rpc();
+    };
+
+    let expiry = (await getNow()) + 60;
+    await claim(operator, 30, expiry);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("30");
+    expect((await program.account.recipientReceipt.fetch(receipt)).claimed.map(c => c.toString())).to.deep.equal(["30"]);
+    await expectError(claim(operator, 30, expiry), "AuthorizationAlreadyUsed");
+    await expectError(claim(operator, 30, (await getNow()) - 1), "AuthorizationExpired");
+    await expectError(claim(participant, 30, expiry + 1), "InvalidAuthorization");
+    await expectError(claim(operator, 30, expiry + 1, await platformAddress("partner")), "InvalidAuthorization");
+    await expectError(claim(operator, 71, expiry + 1), "InvalidClaimAmount");
+
+    await claim(operator, 70, expiry + 1);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("100");
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+  });
+
+  it("Cap the amount airdropped to a single recipient", async () => {
+    let cappedToken = await createMint(0, 13, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(cappedToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(cappedToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMCAP";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [4, 6, 3].map(amount => ({
+      kind: { token: { mint: cappedToken } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 0
+    }));
+    let createCampaign = (campaignId: string) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(4),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // Campaign ids are part of the receipt seeds, which are at most 32 bytes
+    await expectError(createCampaign(campaignId.padEnd(33, "X")), "CampaignIdTooLong");
+    await createCampaign(campaignId);
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(operator.publicKey, participantATA, participant.publicKey, cappedToken),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 13)
+    ), [operator, campaignCreator]);
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let airdrop = (assetIndex: number) => program.methods.airdrop(campaignId, new anchor.BN(assetIndex)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: cappedToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+
+    let claimed = async (): Promise<string[]> =>
+      ((await program.account.recipientReceipt.fetch(receipt)).claimed as anchor.BN[]).map(c => c.toString());
+
+    // Exactly at the cap
+    await airdrop(0);
+    expect(await claimed()).to.deep.equal(["4", "0", "0"]);
+    // Above the cap
+    await expectError(airdrop(1), "RecipientCapExceeded");
+    // Every asset is capped on its own, whatever was received from the others
+    await airdrop(2);
+    expect(await claimed()).to.deep.equal(["4", "0", "3"]);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("7");
+  });
+
+  it("Top up an asset of a live campaign", async () => {
+    let topUpToken = await createMint(0, 8, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(topUpToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(topUpToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMTOP";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: topUpToken } },
+      availableAmount: new anchor.BN(5),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(operator.publicKey, participantATA, participant.publicKey, topUpToken),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 8)
+    ), [operator, campaignCreator]);
+    await waitUntil(startingTime);
+
+    await program.methods.topUpAsset(campaignId, new anchor.BN(0), new anchor.BN(3)).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == campaignId);
+    expect(campaign?.assets[0]?.availableAmount?.toString()).to.equal("8");
+    expect(campaign?.totalAvailableAssets?.toString()).to.equal("8");
+
+    await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: topUpToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("8");
+    campaigns = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+  });
+
+  it("Operator force-closes a campaign and refunds its fee", async () => {
+    let now = await getNow();
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMFC1";
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let closeCampaign = (signer: anchor.web3.Keypair) => program.methods.adminCloseCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      operator: signer.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([signer]).rpc();
+
+    await expectError(closeCampaign(participant), "ConstraintRaw");
+
+    let creatorBalanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    await closeCampaign(operator);
+    let creatorBalanceAfter = await connection.getBalance(campaignCreator.publicKey);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+    expect((creatorBalanceAfter - creatorBalanceBefore).toString()).to.equal("100000000");
+  });
+
+  it("Create campaign without fee transfer on a zero-fee platform", async () => {
+    let setMinFee = (minFee: number) => program.methods.setFeeBounds(new anchor.BN(minFee), new anchor.BN(1000000000)).accounts({
       admin: admin.publicKey,
       airdropPlatform,
       systemProgram: anchor.web3.SystemProgram.programId
     }).signers([admin]).rpc();
-    let airdropPlatformBalance = await connection.getBalance(airdropPlatform);
-    expect(airdropPlatformBalance.toString()).to.equal("0");
+    let setFee = (fee: number) => program.methods.setFeePerAsset(new anchor.BN(fee)).accounts({
+      operator: operator.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    await setMinFee(0);
+    await setFee(0);
+
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    let signature = await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMZF1",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc({ commitment: "confirmed" });
+    let transaction = await connection.getTransaction(signature, { commitment: "confirmed" });
+    expect(transaction.meta.innerInstructions.flatMap(ix => ix.instructions).length).to.equal(0);
+
+    // Restore the fee for the following tests
+    await setFee(100000000);
+    await setMinFee(10000000);
   });
-});
+
+  it("List operators page by page", async () => {
+    let extraOperators = [...Array(10)].map(() => anchor.web3.Keypair.generate().publicKey);
+    let setExtraOperators = (isOperator: boolean) => program.methods.setOperators(extraOperators, extraOperators.map(() => isOperator)).accounts({
+      airdropPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    await setExtraOperators(true);
+
+    // The return data is a borsh-encoded vector: a u32 length followed by the public keys
+    let listOperators = async (offset: number, limit: number): Promise<string[]> => {
+      let data = await viewReturnData(await program.methods.listOperators(offset, limit).accounts({ airdropPlatform }).instruction());
+      return [...Array(data.readUInt32LE(0)).keys()].map(i => new PublicKey(data.slice(4 + 32 * i, 36 + 32 * i)).toString());
+    };
+    let operators = (await program.account.airdropPlatform.fetch(airdropPlatform)).operators.map(op => op.toString());
+    expect(operators.length).to.equal(12);
+    let firstPage = await listOperators(2, 5);
+    let secondPage = await listOperators(7, 5);
+    expect([...firstPage, ...secondPage]).to.deep.equal(extraOperators.map(op => op.toString()));
+    expect(await listOperators(10, 5)).to.deep.equal(operators.slice(10));
+
+    await setExtraOperators(false);
+  });
+
+  it("Airdrop only to the recipient's canonical ATA", async () => {
+    let ataToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(ataToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(ataToken, participant.publicKey);
+    let otherAccount = anchor.web3.Keypair.generate();
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMATA";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: ataToken } },
+      availableAmount: new anchor.BN(2),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    // Besides the canonical ATA, the participant owns a token account at an arbitrary address
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(operator.publicKey, participantATA, participant.publicKey, ataToken),
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: operator.publicKey,
+        newAccountPubkey: otherAccount.publicKey,
+        space: ACCOUNT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+        lamports: await connection.getMinimumBalanceForRentExemption(ACCOUNT_SIZE)
+      }),
+      createInitializeAccountInstruction(otherAccount.publicKey, ataToken, participant.publicKey),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 2)
+    ), [operator, otherAccount, campaignCreator]);
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let airdrop = (recipientAta: PublicKey) => program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta,
+      recipient: participant.publicKey,
+      mint: ataToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    await expectError(airdrop(otherAccount.publicKey), "InvalidRecipientAta");
+    await airdrop(participantATA);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("2");
+  });
+
+  it("Airdrop to a wallet that never held the mint", async () => {
+    let freshWallet = anchor.web3.Keypair.generate();
+    let freshToken = await createMint(0, 3, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(freshToken, campaignCreator.publicKey);
+    let freshWalletATA = await getAssociatedTokenAddress(freshToken, freshWallet.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMNEW";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: freshToken } },
+      availableAmount: new anchor.BN(3),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 3)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    expect(await connection.getAccountInfo(freshWalletATA)).to.equal(null);
+    await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: freshWalletATA,
+      recipient: freshWallet.publicKey,
+      mint: freshToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, freshWallet.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let freshWalletAccount = await getAccount(connection, freshWalletATA);
+    expect(freshWalletAccount.owner.toString()).to.equal(freshWallet.publicKey.toString());
+    expect(freshWalletAccount.amount.toString()).to.equal("3");
+  });
+
+  it("Fix the metadata URI during the edit grace period only", async () => {
+    let setEditGracePeriod = (editGracePeriod: number) => program.methods.setEditGracePeriod(new anchor.BN(editGracePeriod)).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    await setEditGracePeriod(10);
+
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMGRC";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let updateCampaign = (newStartingTime: number, metadataUri: string) => program.methods.updateCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(newStartingTime),
+      new anchor.BN(0),
+      metadataUri,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let fetchMetadataUri = async () => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.find(c => c.campaignId == campaignId)?.metadataUri;
+    };
+
+    // Right at the start, within the grace period
+    await waitUntil(startingTime);
+    await updateCampaign(startingTime, "https://playlink.network/campaigns/grace.json");
+    expect(await fetchMetadataUri()).to.equal("https://playlink.network/campaigns/grace.json");
+    await expectError(updateCampaign(startingTime + 60, "https://playlink.network/campaigns/grace.json"), "UpdateNotAllowed");
+
+    // Once the grace period has elapsed
+    await waitUntil(startingTime + 10);
+    await expectError(updateCampaign(startingTime, "https://playlink.network/campaigns/late.json"), "UpdateNotAllowed");
+    expect(await fetchMetadataUri()).to.equal("https://playlink.network/campaigns/grace.json");
+
+    await setEditGracePeriod(0);
+  });
+
+  it("Track the total distributed by a campaign", async () => {
+    let trackedToken = await createMint(0, 21, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(trackedToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(trackedToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMDST";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [5, 7, 9].map(amount => ({
+      kind: { token: { mint: trackedToken } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 0
+    }));
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 21)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let fetchCampaign = async () => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.find(c => c.campaignId == campaignId);
+    };
+    expect((await fetchCampaign())?.totalDistributed?.toString()).to.equal("0");
+    for (let [assetIndex, distributed] of [[0, 5], [1, 12]]) {
+      let signature = await program.methods.airdrop(campaignId, new anchor.BN(assetIndex)).accounts({
+        creatorAta: creatorATA,
+        recipientAta: participantATA,
+        recipient: participant.publicKey,
+        mint: trackedToken,
+        campaignCreator: campaignCreator.publicKey,
+        operator: operator.publicKey,
+        receipt,
+        airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([operator]).rpc({ commitment: "confirmed" });
+      let campaign = await fetchCampaign();
+      expect(campaign?.totalDistributed?.toString()).to.equal(distributed.toString());
+      expect(campaign?.totalDistributed?.add(campaign?.totalAvailableAssets)?.toString()).to.equal("21");
+      let event = (await transactionEvents(signature)).find(e => e.name == "AirdropExecuted");
+      expect(event?.data?.totalDistributed?.toString()).to.equal(distributed.toString());
+    }
+  });
+
+  it("Keep a multi-asset campaign until every asset is drained", async () => {
+    let multiToken = await createMint(0, 5, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(multiToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(multiToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMMLT";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [2, 3].map(amount => ({
+      kind: { token: { mint: multiToken } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 0
+    }));
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 5)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let airdrop = (assetIndex: number) => program.methods.airdrop(campaignId, new anchor.BN(assetIndex)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: multiToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let fetchCampaign = async () => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.find(c => c.campaignId == campaignId);
+    };
+
+    await airdrop(1);
+    let campaign = await fetchCampaign();
+    expect(campaign?.assets[0]?.availableAmount?.toString()).to.equal("2");
+    expect(campaign?.assets[1]?.availableAmount?.toString()).to.equal("0");
+    expect(campaign?.totalAvailableAssets?.toString()).to.equal("2");
+    await airdrop(0);
+    expect(await fetchCampaign()).to.equal(undefined);
+  });
+
+  it("Run an independent partner platform", async () => {
+    let partnerPlatform = await platformAddress("partner");
+    await program.methods.initialize(
+      "partner",
+      new anchor.BN(50000000),
+      new anchor.BN(10000000),
+      new anchor.BN(1000000000),
+      0
+    ).accounts({
+      airdropPlatform: partnerPlatform,
+      admin: operator.publicKey,
+      payer: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    let createCampaign = (platform: PublicKey, campaignId: string) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform: platform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let campaignIds = async (platform: PublicKey): Promise<string[]> =>
+      ((await program.account.airdropPlatform.fetch(platform)).allCampaigns as any[]).map(c => c.campaignId);
+
+    // The same campaign id can be used on both platforms
+    await createCampaign(partnerPlatform, "01BX5ZZKBKACTAV9WEVGEMMPT1");
+    await createCampaign(airdropPlatform, "01BX5ZZKBKACTAV9WEVGEMMPT1");
+    await createCampaign(partnerPlatform, "01BX5ZZKBKACTAV9WEVGEMMPT2");
+    let partner = await program.account.airdropPlatform.fetch(partnerPlatform);
+    expect(partner.platformId).to.equal("partner");
+    expect(partner.admin.toString()).to.equal(operator.publicKey.toString());
+    expect(await campaignIds(partnerPlatform)).to.deep.equal(["01BX5ZZKBKACTAV9WEVGEMMPT1", "01BX5ZZKBKACTAV9WEVGEMMPT2"]);
+    expect(await campaignIds(airdropPlatform)).to.include("01BX5ZZKBKACTAV9WEVGEMMPT1");
+    expect(await campaignIds(airdropPlatform)).to.not.include("01BX5ZZKBKACTAV9WEVGEMMPT2");
+  });
+
+  it("Pause a single campaign", async () => {
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let campaigns = [];
+    for (let campaignId of ["01BX5ZZKBKACTAV9WEVGEMMPS1", "01BX5ZZKBKACTAV9WEVGEMMPS2"]) {
+      let mint = await createMint(0, 1, campaignCreator.publicKey);
+      let creatorATA = await getAssociatedTokenAddress(mint, campaignCreator.publicKey);
+      let assets: any[] = [{
+        kind: { token: { mint: mint } },
+        availableAmount: new anchor.BN(1),
+        isNft: false,
+        decimals: 0
+      }];
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+      await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+        createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+      ), [campaignCreator]);
+      campaigns.push({ campaignId, mint, creatorATA });
+    }
+    let [paused, running] = campaigns;
+    let setCampaignPaused = (isPaused: boolean) => program.methods.setCampaignPaused(paused.campaignId, campaignCreator.publicKey, isPaused).accounts({
+      airdropPlatform,
+      operator: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let airdrop = async ({ campaignId, mint, creatorATA }) => program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: await getAssociatedTokenAddress(mint, participant.publicKey),
+      recipient: participant.publicKey,
+      mint,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+
+    await setCampaignPaused(true);
+    await waitUntil(startingTime);
+    await expectError(airdrop(paused), "CampaignPaused");
+    await airdrop(running);
+    await setCampaignPaused(false);
+    await airdrop(paused);
+    for (let { mint } of campaigns) {
+      let participantATA = await getAssociatedTokenAddress(mint, participant.publicKey);
+      expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("1");
+    }
+  });
+
+  it("Airdrop SOL to two recipients", async () => {
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let recipients = [participant.publicKey, anchor.web3.Keypair.generate().publicKey];
+    let assets: any[] = [1000000000, 2000000000].map(amount => ({
+      kind: { sol: {} },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 9
+    }));
+    let platformBalanceBefore = await connection.getBalance(airdropPlatform);
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMSOL",
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    // The SOL to airdrop is deposited to the platform along with the fee
+    expect((await connection.getBalance(airdropPlatform)) - platformBalanceBefore).to.equal(3000000000 + 2 * 100000000);
+
+    await waitUntil(startingTime);
+    for (let [assetIndex, recipientAddress] of recipients.entries()) {
+      let balanceBefore = await connection.getBalance(recipientAddress);
+      await program.methods.airdropSol("01BX5ZZKBKACTAV9WEVGEMMSOL", new anchor.BN(assetIndex)).accounts({
+        recipient: recipientAddress,
+        campaignCreator: campaignCreator.publicKey,
+        operator: operator.publicKey,
+        receipt: await receiptAddress("01BX5ZZKBKACTAV9WEVGEMMSOL", recipientAddress),
+        airdropPlatform,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([operator]).rpc();
+      expect((await connection.getBalance(recipientAddress)) - balanceBefore).to.equal(assets[assetIndex].availableAmount.toNumber());
+    }
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMSOL")).to.equal(false);
+  });
+
+  it("Reject campaign starting too soon to be funded", async () => {
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await expectError(
+      program.methods.createAirdropCampaign(
+        "01BX5ZZKBKACTAV9WEVGEMMSON",
+        assets,
+        new anchor.BN(now + 30),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc(),
+      "LowStartingTime"
+    );
+  });
+
+  it("Re-run initialization without touching an initialized platform", async () => {
+    let rerunPlatform = await platformAddress("rerun");
+    let initializeIfNeeded = (feePerAsset: number) => program.methods.initializeIfNeeded(
+      "rerun",
+      new anchor.BN(feePerAsset),
+      new anchor.BN(10000000),
+      new anchor.BN(1000000000),
+      0
+    ).accounts({
+      airdropPlatform: rerunPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+
+    await initializeIfNeeded(50000000);
+    let initialized = await program.account.airdropPlatform.fetch(rerunPlatform);
+    expect(initialized.admin.toString()).to.equal(admin.publicKey.toString());
+    expect(initialized.feePerAsset.toString()).to.equal("50000000");
+
+    // The second call is a no-op
+    await initializeIfNeeded(70000000);
+    let rerun = await program.account.airdropPlatform.fetch(rerunPlatform);
+    expect(rerun.feePerAsset.toString()).to.equal("50000000");
+    expect(rerun.operators.map(op => op.toString())).to.deep.equal([admin.publicKey.toString()]);
+  });
+
+  it("Read the remaining amount of every asset", async () => {
+    let progressToken = await createMint(0, 12, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(progressToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMAMT";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [4, 8].map(amount => ({
+      kind: { token: { mint: progressToken } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 0
+    }));
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 12)
+    ), [campaignCreator]);
+
+    // The return data is a borsh-encoded vector: a u32 length followed by the amounts
+    let getAssetAmounts = async (): Promise<string[]> => {
+      let data = await viewReturnData(await program.methods.getAssetAmounts(campaignId, campaignCreator.publicKey).accounts({ airdropPlatform }).instruction());
+      return [...Array(data.readUInt32LE(0)).keys()].map(i => data.readBigUInt64LE(4 + 8 * i).toString());
+    };
+    expect(await getAssetAmounts()).to.deep.equal(["4", "8"]);
+
+    await waitUntil(startingTime);
+    await program.methods.airdrop(campaignId, new anchor.BN(1)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: await getAssociatedTokenAddress(progressToken, participant.publicKey),
+      recipient: participant.publicKey,
+      mint: progressToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    expect(await getAssetAmounts()).to.deep.equal(["4", "0"]);
+  });
+
+  it("Leave the campaign untouched when the transfer fails", async () => {
+    let failingToken = await createMint(0, 10, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(failingToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMFLT";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: failingToken } },
+      availableAmount: new anchor.BN(10),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    // The creator holds enough tokens but delegates too few of them for the transfer to succeed
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 5)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let fetchCampaign = async () => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.find(c => c.campaignId == campaignId);
+    };
+    await expectError(
+      program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+        creatorAta: creatorATA,
+        recipientAta: await getAssociatedTokenAddress(failingToken, participant.publicKey),
+        recipient: participant.publicKey,
+        mint: failingToken,
+        campaignCreator: campaignCreator.publicKey,
+        operator: operator.publicKey,
+        receipt: await receiptAddress(campaignId, participant.publicKey),
+        airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([operator]).rpc(),
+      "insufficient funds"
+    );
+    let campaign = await fetchCampaign();
+    expect(campaign?.assets[0]?.availableAmount?.toString()).to.equal("10");
+    expect(campaign?.totalAvailableAssets?.toString()).to.equal("10");
+    expect(campaign?.totalDistributed?.toString()).to.equal("0");
+  });
+
+  it("Deny a mint from being airdropped", async () => {
+    let deniedToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(deniedToken, campaignCreator.publicKey);
+    let setMintDenied = (denied: boolean) => program.methods.setMintDenied(deniedToken, denied).accounts({
+      airdropPlatform,
+      operator: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: deniedToken } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    let createCampaign = (campaignId: string) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // Creating a campaign with a denied mint
+    await setMintDenied(true);
+    await expectError(createCampaign("01BX5ZZKBKACTAV9WEVGEMMDN1"), "MintDenied");
+
+    // Denying the mint after the campaign was created
+    await setMintDenied(false);
+    await createCampaign("01BX5ZZKBKACTAV9WEVGEMMDN2");
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+    ), [campaignCreator]);
+    await setMintDenied(true);
+    await waitUntil(startingTime);
+    await expectError(
+      program.methods.airdrop("01BX5ZZKBKACTAV9WEVGEMMDN2", new anchor.BN(0)).accounts({
+        creatorAta: creatorATA,
+        recipientAta: await getAssociatedTokenAddress(deniedToken, participant.publicKey),
+        recipient: participant.publicKey,
+        mint: deniedToken,
+        campaignCreator: campaignCreator.publicKey,
+        operator: operator.publicKey,
+        receipt: await receiptAddress("01BX5ZZKBKACTAV9WEVGEMMDN2", participant.publicKey),
+        airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([operator]).rpc(),
+      "MintDenied"
+    );
+    await setMintDenied(false);
+  });
+
+  it("Charge a daily fee over the campaign duration", async () => {
+    let setFeePerAssetPerDay = (fee: number) => program.methods.setFeePerAssetPerDay(new anchor.BN(fee)).accounts({
+      operator: operator.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    await setFeePerAssetPerDay(1000000);
+
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    let campaignFee = async (campaignId: string, days: number): Promise<string> => {
+      let startingTime = now + 30 * 60;
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(startingTime + days * 24 * 60 * 60),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.find(c => c.campaignId == campaignId)?.airdropFee?.toString();
+    };
+    expect(await campaignFee("01BX5ZZKBKACTAV9WEVGEMMD01", 1)).to.equal("101000000");
+    expect(await campaignFee("01BX5ZZKBKACTAV9WEVGEMMD30", 30)).to.equal("130000000");
+
+    // Without a daily rate, the flat fee applies whatever the duration
+    await setFeePerAssetPerDay(0);
+    expect(await campaignFee("01BX5ZZKBKACTAV9WEVGEMMDFL", 30)).to.equal("100000000");
+
+    // Extending a campaign is charged at the daily rate of its creation, not the current one
+    let startingTime = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns
+      .find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMD01").startingTime.toNumber();
+    await program.methods.updateCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMD01",
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(startingTime + 30 * 24 * 60 * 60),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMD01").feePerAssetPerDay.toString()).to.equal("1000000");
+    expect(campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMD01").airdropFee.toString()).to.equal("130000000");
+  });
+
+  it("Track when each operator was last active", async () => {
+    // The return data is a borsh-encoded vector: a u32 length followed by (public key, i64) pairs
+    let lastActive = async (account: PublicKey): Promise<number> => {
+      let data = await viewReturnData(await program.methods.getOperatorActivity().accounts({ airdropPlatform }).instruction());
+      let activity = [...Array(data.readUInt32LE(0)).keys()].map(i => ({
+        operator: new PublicKey(data.slice(4 + 40 * i, 36 + 40 * i)),
+        lastActive: Number(data.readBigInt64LE(36 + 40 * i))
+      }));
+      return activity.find(a => a.operator.equals(account)).lastActive;
+    };
+
+    let heartbeatToken = await createMint(0, 1, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(heartbeatToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMHBT";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: heartbeatToken } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+    ), [campaignCreator]);
+    let before = await lastActive(operator.publicKey);
+    await waitUntil(startingTime);
+
+    await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: await getAssociatedTokenAddress(heartbeatToken, participant.publicKey),
+      recipient: participant.publicKey,
+      mint: heartbeatToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    expect(await lastActive(operator.publicKey)).to.be.greaterThan(before);
+    expect(await lastActive(operator.publicKey)).to.be.at.least(startingTime);
+  });
+
+  it("Transfer a campaign to a new creator", async () => {
+    let newCreator = anchor.web3.Keypair.generate();
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMTRF";
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let transferCampaign = (signer: anchor.web3.Keypair) => program.methods.transferCampaign(campaignId, newCreator.publicKey).accounts({
+      airdropPlatform,
+      campaignCreator: signer.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([signer]).rpc({ commitment: "confirmed" });
+    let fetchCreator = async () => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.find(c => c.campaignId == campaignId)?.creator?.toString();
+    };
+
+    // Only the current creator can transfer the campaign
+    await expectError(transferCampaign(participant), "CampaignNotExists");
+    expect(await fetchCreator()).to.equal(campaignCreator.publicKey.toString());
+
+    let signature = await transferCampaign(campaignCreator);
+    expect(await fetchCreator()).to.equal(newCreator.publicKey.toString());
+    let event = (await transactionEvents(signature)).find(e => e.name == "CampaignCreatorChanged");
+    expect(event?.data?.previousCreator?.toString()).to.equal(campaignCreator.publicKey.toString());
+    expect(event?.data?.newCreator?.toString()).to.equal(newCreator.publicKey.toString());
+  });
+
+  it("Simulate an airdrop without moving tokens", async () => {
+    let simulatedToken = await createMint(0, 3, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(simulatedToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(simulatedToken, participant.publicKey);
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, participantATA, participant.publicKey, simulatedToken)
+    ));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMSIM";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: simulatedToken } },
+      availableAmount: new anchor.BN(3),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 3)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let simulateAirdrop = async (recipientAddress: PublicKey) => program.methods.simulateAirdrop(campaignId, new anchor.BN(0), new anchor.BN(3)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: await getAssociatedTokenAddress(simulatedToken, recipientAddress),
+      recipient: recipientAddress,
+      mint: simulatedToken,
+      campaignCreator: campaignCreator.publicKey,
+      receipt: await receiptAddress(campaignId, recipientAddress),
+      airdropPlatform
+    }).rpc();
+
+    // Would succeed, yet nothing moves
+    await simulateAirdrop(participant.publicKey);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("0");
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.find(c => c.campaignId == campaignId)?.totalAvailableAssets?.toString()).to.equal("3");
+
+    // Would fail, the recipient has no ATA for this mint
+    await expectError(simulateAirdrop(anchor.web3.Keypair.generate().publicKey), "InvalidRecipientAta");
+  });
+
+  it("Keep drained campaigns as records only when configured", async () => {
+    let setKeepDrainedCampaigns = (keep: boolean) => program.methods.setKeepDrainedCampaigns(keep).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let campaigns = [];
+    for (let campaignId of ["01BX5ZZKBKACTAV9WEVGEMMRM1", "01BX5ZZKBKACTAV9WEVGEMMKP1"]) {
+      let mint = await createMint(0, 1, campaignCreator.publicKey);
+      let creatorATA = await getAssociatedTokenAddress(mint, campaignCreator.publicKey);
+      let assets: any[] = [{
+        kind: { token: { mint } },
+        availableAmount: new anchor.BN(1),
+        isNft: false,
+        decimals: 0
+      }];
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+      await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+        createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+      ), [campaignCreator]);
+      campaigns.push({ campaignId, mint, creatorATA, assets });
+    }
+    let airdrop = async ({ campaignId, mint, creatorATA }) => program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: await getAssociatedTokenAddress(mint, participant.publicKey),
+      recipient: participant.publicKey,
+      mint,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let fetchCampaign = async (campaignId: string) => {
+      let allCampaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return allCampaigns.find(c => c.campaignId == campaignId);
+    };
+    let [removed, kept] = campaigns;
+    await waitUntil(startingTime);
+
+    // By default, a drained campaign is removed
+    await airdrop(removed);
+    expect(await fetchCampaign(removed.campaignId)).to.equal(undefined);
+
+    // Otherwise it is kept, marked as completed, and its id cannot be reused
+    await setKeepDrainedCampaigns(true);
+    await airdrop(kept);
+    let completed = await fetchCampaign(kept.campaignId);
+    expect(completed?.completed).to.equal(true);
+    expect(completed?.totalAvailableAssets?.toString()).to.equal("0");
+    await expectError(
+      program.methods.createAirdropCampaign(
+        kept.campaignId,
+        kept.assets,
+        new anchor.BN((await getNow()) + 30 * 60),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(kept.assets)).signers([campaignCreator]).rpc(),
+      "CampaignAlreadyCreated"
+    );
+    await setKeepDrainedCampaigns(false);
+  });
+
+  it("Create several campaigns in a single instruction", async () => {
+    let now = await getNow();
+    let campaignInput = (campaignId: string, assetCount: number) => ({
+      campaignId,
+      assets: [...Array(assetCount)].map(() => ({
+        kind: { token: { mint: token1.publicKey } },
+        availableAmount: new anchor.BN(1000),
+        isNft: false,
+        decimals: 9
+      })),
+      startingTime: new anchor.BN(now + 30 * 60),
+      endingTime: new anchor.BN(0),
+      metadataUri: null,
+      category: null,
+      maxPerRecipient: new anchor.BN(0)
+    });
+    let campaigns = [
+      campaignInput("01BX5ZZKBKACTAV9WEVGEMMBT1", 1),
+      campaignInput("01BX5ZZKBKACTAV9WEVGEMMBT2", 2),
+      campaignInput("01BX5ZZKBKACTAV9WEVGEMMBT3", 1)
+    ];
+    let createCampaignsBatch = (campaigns: any[]) => program.methods.createCampaignsBatch(campaigns).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(campaigns.flatMap(campaign => assetMints(campaign.assets))).signers([campaignCreator]).rpc();
+
+    // Ids must be unique within the batch
+    await expectError(createCampaignsBatch([campaigns[0], campaigns[0]]), "CampaignAlreadyCreated");
+
+    let platformBalanceBefore = await connection.getBalance(airdropPlatform);
+    await createCampaignsBatch(campaigns);
+    let platformBalanceAfter = await connection.getBalance(airdropPlatform);
+    expect((platformBalanceAfter - platformBalanceBefore).toString()).to.equal("400000000");
+    let allCampaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    for (let { campaignId, assets } of campaigns) {
+      let campaign = allCampaigns.find(c => c.campaignId == campaignId);
+      expect(campaign?.assets?.length).to.equal(assets.length);
+      expect(campaign?.airdropFee?.toString()).to.equal((assets.length * 100000000).toString());
+    }
+
+    // Ids must be unique against existing campaigns too
+    await expectError(createCampaignsBatch([campaigns[1]]), "CampaignAlreadyCreated");
+  });
+
+  it("Reject an update whose extra fee the creator cannot pay", async () => {
+    let poorCreator = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({ fromPubkey: defaultWallet.publicKey, toPubkey: poorCreator.publicKey, lamports: 150000000 })
+    ));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMPOR";
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: poorCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([poorCreator]).rpc();
+
+    // Doubling the assets doubles the fee, which the creator cannot afford anymore
+    let moreAssets = [...assets, ...assets];
+    await expectError(
+      program.methods.updateCampaign(
+        campaignId,
+        moreAssets,
+        new anchor.BN(now + 60 * 60),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0)
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: poorCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(moreAssets)).signers([poorCreator]).rpc(),
+      "InsufficientFeeBalance"
+    );
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == campaignId);
+    expect(campaign?.assets?.length).to.equal(1);
+    expect(campaign?.startingTime?.toString()).to.equal((now + 30 * 60).toString());
+    expect(campaign?.airdropFee?.toString()).to.equal("100000000");
+  });
+
+  it("Admin sweeps tokens stuck in a platform-owned account", async () => {
+    let strayToken = await createMint(0, 4, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(strayToken, campaignCreator.publicKey);
+    let platformATA = await getAssociatedTokenAddress(strayToken, airdropPlatform, true);
+    let adminATA = await getAssociatedTokenAddress(strayToken, admin.publicKey);
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, platformATA, airdropPlatform, strayToken),
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, adminATA, admin.publicKey, strayToken),
+      createMintToInstruction(strayToken, platformATA, defaultWallet.publicKey, 7),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 4)
+    ), [campaignCreator]);
+    let sweepTokens = (source: PublicKey, amount: number) => program.methods.adminSweepTokens(new anchor.BN(amount)).accounts({
+      admin: admin.publicKey,
+      source,
+      destination: adminATA,
+      mint: strayToken,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).signers([admin]).rpc();
+
+    await sweepTokens(platformATA, 7);
+    expect((await getAccount(connection, platformATA)).amount.toString()).to.equal("0");
+    expect((await getAccount(connection, adminATA)).amount.toString()).to.equal("7");
+
+    // A creator's ATA merely delegated to the platform cannot be swept
+    await expectError(sweepTokens(creatorATA, 4), "ConstraintTokenOwner");
+    expect((await getAccount(connection, creatorATA)).amount.toString()).to.equal("4");
+  });
+
+  it("Enforce a cooldown between airdrops to the same recipient", async () => {
+    let dripToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(dripToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMCLD";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [1, 1].map(amount => ({
+      kind: { token: { mint: dripToken } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 0
+    }));
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await program.methods.setRecipientCooldown(campaignId, new anchor.BN(5)).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 2)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let participantATA = await getAssociatedTokenAddress(dripToken, participant.publicKey);
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let airdrop = (assetIndex: number) => program.methods.airdrop(campaignId, new anchor.BN(assetIndex)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: dripToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    await airdrop(0);
+    let firstClaimTime = (await program.account.recipientReceipt.fetch(receipt)).lastClaimTime.toNumber();
+
+    // An immediate second airdrop is rejected, one after the cooldown is allowed
+    await expectError(airdrop(1), "CooldownActive");
+    await waitUntil(firstClaimTime + 5);
+    await airdrop(1);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("2");
+  });
+
+  it("Freeze airdropped tokens until the recipient is cleared", async () => {
+    // The platform holds the freeze authority of this mint
+    let complianceToken = anchor.web3.Keypair.generate();
+    let creatorATA = await getAssociatedTokenAddress(complianceToken.publicKey, campaignCreator.publicKey);
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: defaultWallet.publicKey,
+        newAccountPubkey: complianceToken.publicKey,
+        space: MINT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+        lamports: await connection.getMinimumBalanceForRentExemption(MINT_SIZE)
+      }),
+      createInitializeMintInstruction(complianceToken.publicKey, 0, defaultWallet.publicKey, airdropPlatform),
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, creatorATA, campaignCreator.publicKey, complianceToken.publicKey),
+      createMintToInstruction(complianceToken.publicKey, creatorATA, defaultWallet.publicKey, 1)
+    ), [complianceToken]);
+
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMFRZ";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: complianceToken.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await program.methods.setFreezeOnAirdrop(campaignId, true).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let participantATA = await getAssociatedTokenAddress(complianceToken.publicKey, participant.publicKey);
+    await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: complianceToken.publicKey,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let airdropped = await getAccount(connection, participantATA);
+    expect(airdropped.amount.toString()).to.equal("1");
+    expect(airdropped.isFrozen).to.equal(true);
+
+    await program.methods.unfreezeRecipient().accounts({
+      recipientAta: participantATA,
+      mint: complianceToken.publicKey,
+      operator: operator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).signers([operator]).rpc();
+    expect((await getAccount(connection, participantATA)).isFrozen).to.equal(false);
+  });
+
+  it("Cap the total number of campaigns on the platform", async () => {
+    let setMaxTotalCampaigns = (maxTotalCampaigns: number) => program.methods.setMaxTotalCampaigns(maxTotalCampaigns).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let campaignCount = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns.length;
+    await setMaxTotalCampaigns(campaignCount + 2);
+
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    let createCampaign = (campaignId: string) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await createCampaign("01BX5ZZKBKACTAV9WEVGEMMFL1");
+    await createCampaign("01BX5ZZKBKACTAV9WEVGEMMFL2");
+    await expectError(createCampaign("01BX5ZZKBKACTAV9WEVGEMMFL3"), "PlatformFull");
+
+    await setMaxTotalCampaigns(0);
+    await createCampaign("01BX5ZZKBKACTAV9WEVGEMMFL3");
+  });
+
+  it("Vest a campaign linearly over time", async () => {
+    let vestedToken = await createMint(0, 4, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMVST";
+    let now = await getNow();
+    let startingTime = now + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: vestedToken } },
+      availableAmount: new anchor.BN(4),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // Vest over 40 seconds, starting shortly
+    let vestingStart = now + 15;
+    await expectError(program.methods.setVesting(campaignId, new anchor.BN(vestingStart), new anchor.BN(vestingStart)).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc(), "InvalidVestingSchedule");
+    await program.methods.setVesting(campaignId, new anchor.BN(vestingStart), new anchor.BN(vestingStart + 40)).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+
+    // The return data is a borsh-encoded u64
+    let claimableAmount = async (): Promise<string> => {
+      let data = await viewReturnData(await program.methods.claimableAmount(campaignId, campaignCreator.publicKey, new anchor.BN(0), participant.publicKey).accounts({
+        receipt: await receiptAddress(campaignId, participant.publicKey),
+        airdropPlatform
+      }).instruction());
+      return data.readBigUInt64LE(0).toString();
+    };
+    expect(await claimableAmount()).to.equal("0");
+
+    // A quarter of the way through, a quarter of the asset is claimable
+    await waitUntil(vestingStart + 12);
+    expect(await claimableAmount()).to.equal("1");
+  });
+
+  it("Cancel a campaign before it starts", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMCNL";
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN((await getNow()) + MIN_START_DELAY + 5),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let airdropFee = campaigns.find(c => c.campaignId == campaignId).airdropFee.toNumber();
+
+    // The event reports the fee refunded to the creator
+    let balanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    let signature = await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc({ commitment: "confirmed" });
+    let event = (await transactionEvents(signature)).find(e => e.name == "CampaignCancelled");
+    expect(event.data.campaignId).to.equal(campaignId);
+    expect(event.data.creator.toBase58()).to.equal(campaignCreator.publicKey.toBase58());
+    expect(event.data.refundedFee.toNumber()).to.equal(airdropFee);
+    expect((await connection.getBalance(campaignCreator.publicKey)) - balanceBefore).to.equal(airdropFee);
+    campaigns = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+  });
+
+  it("Reclaim the SOL left in an ended campaign", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMRCL";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { sol: {} },
+      availableAmount: new anchor.BN(500000000),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(startingTime + 2),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let reclaimCampaign = () => program.methods.reclaimCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc({ commitment: "confirmed" });
+    await expectError(reclaimCampaign(), "CampaignNotEnded");
+
+    // Once ended, the SOL nobody received goes back to the creator
+    await waitUntil(startingTime + 3);
+    let balanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    let event = (await transactionEvents(await reclaimCampaign())).find(e => e.name == "CampaignReclaimed");
+    expect(event.data.campaignId).to.equal(campaignId);
+    expect(event.data.creator.toBase58()).to.equal(campaignCreator.publicKey.toBase58());
+    expect(event.data.reclaimedAmount.toNumber()).to.equal(500000000);
+    expect((await connection.getBalance(campaignCreator.publicKey)) - balanceBefore).to.equal(500000000);
+  });
+
+  it("Reject adding operators past the limit", async () => {
+    let setOperators = (operators: PublicKey[]) => program.methods.setOperators(operators, operators.map(() => true)).accounts({
+      airdropPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let operatorsBefore = (await program.account.airdropPlatform.fetch(airdropPlatform)).operators.length;
+
+    // Too many in a single call, or too many in total
+    let newOperators = (count: number) => [...Array(count)].map(() => anchor.web3.Keypair.generate().publicKey);
+    await expectError(setOperators(newOperators(17)), "TooManyOperators");
+    await expectError(setOperators(newOperators(17 - operatorsBefore)), "TooManyOperators");
+    expect((await program.account.airdropPlatform.fetch(airdropPlatform)).operators.length).to.equal(operatorsBefore);
+  });
+
+  it("Reject claims after the claim window closes", async () => {
+    let windowToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(windowToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(windowToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMWND";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: windowToken } },
+      availableAmount: new anchor.BN(2),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await program.methods.setClaimWindow(campaignId, new anchor.BN(10)).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(participant.publicKey, participantATA, participant.publicKey, windowToken),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 2)
+    ), [participant, campaignCreator]);
+    await waitUntil(startingTime);
+
+    let claim = async (expiry: number) => {
+      let message = Buffer.concat([
+        program.programId.toBuffer(),
+        airdropPlatform.toBuffer(),
+        campaignCreator.publicKey.toBuffer(),
+        Buffer.from(campaignId),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+        participant.publicKey.toBuffer(),
+        new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+        new anchor.BN(expiry).toArrayLike(Buffer, "le", 8)
+      ]);
+      let verifyIx = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({ privateKey: operator.secretKey, message });
+      return program.methods.claimWithAuthorization(
+        campaignId,
+        new anchor.BN(0),
+        new anchor.BN(1),
+        new anchor.BN(expiry),
+        Array.from(verifyIx.data.slice(48, 112))
+      ).accounts({
+        creatorAta: creatorATA,
+        recipientAta: participantATA,
+        mint: windowToken,
+        campaignCreator: campaignCreator.publicKey,
+        recipient: participant.publicKey,
+        receipt: await receiptAddress(campaignId, participant.publicKey),
+        airdropPlatform,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).preInstructions([verifyIx]).signers([participant]).rpc();
+    };
+
+    // Claiming within the window works, claiming after it does not
+    let expiry = startingTime + 60;
+    await claim(expiry);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("1");
+    await waitUntil(startingTime + 11);
+    await expectError(claim(expiry + 1), "ClaimWindowClosed");
+  });
+
+  it("Report asset decimals in the creation event", async () => {
+    let usdLike = await createMint(6, 5000000, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMDEC";
+    let assets: any[] = [{
+      kind: { token: { mint: usdLike } },
+      availableAmount: new anchor.BN(2500000),
+      isNft: false,
+      decimals: 6
+    }];
+    let signature = await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN((await getNow()) + MIN_START_DELAY + 5),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc({ commitment: "confirmed" });
+
+    // Indexers can format 2500000 base units as 2.5 tokens
+    let event = (await transactionEvents(signature)).find(e => e.name == "AirdropCampaignCreated");
+    expect(event.data.assets[0].decimals).to.equal(6);
+    expect(event.data.assets[0].availableAmount.toString()).to.equal("2500000");
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Re-delegate the assets of two campaigns at once", async () => {
+    let mints = [await createMint(0, 3, campaignCreator.publicKey), await createMint(0, 5, campaignCreator.publicKey)];
+    let creatorATAs = await Promise.all(mints.map(mint => getAssociatedTokenAddress(mint, campaignCreator.publicKey)));
+    let campaignIds = ["01BX5ZZKBKACTAV9WEVGEMMRD1", "01BX5ZZKBKACTAV9WEVGEMMRD2"];
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    for (let [i, campaignId] of campaignIds.entries()) {
+      let assets: any[] = [{
+        kind: { token: { mint: mints[i] } },
+        availableAmount: new anchor.BN(i == 0 ? 3 : 5),
+        isNft: false,
+        decimals: 0
+      }];
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    }
+
+    // The delegation went somewhere else, the creator re-delegates both campaigns to the platform
+    let elsewhere = anchor.web3.Keypair.generate().publicKey;
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...creatorATAs.map(ata => createApproveInstruction(ata, elsewhere, campaignCreator.publicKey, 1))
+    ), [campaignCreator]);
+    await program.methods.reauthorizeDelegations(campaignIds).accounts({
+      campaignCreator: campaignCreator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts(creatorATAs.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }))).signers([campaignCreator]).rpc();
+    for (let [i, ata] of creatorATAs.entries()) {
+      let account = await getAccount(connection, ata);
+      expect(account.delegate.toBase58()).to.equal(airdropPlatform.toBase58());
+      expect(account.delegatedAmount.toString()).to.equal(i == 0 ? "3" : "5");
+    }
+
+    for (let campaignId of campaignIds) {
+      await program.methods.cancelCampaign(campaignId).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([campaignCreator]).rpc();
+    }
+  });
+
+  it("Set and read back a campaign category", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMCAT";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    let createCampaign = (category: string) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      category,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let storedCategory = async (): Promise<string> => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.find(c => c.campaignId == campaignId).category;
+    };
+
+    await expectError(createCampaign("g".repeat(33)), "CategoryTooLong");
+    await createCampaign("gaming");
+    expect(await storedCategory()).to.equal("gaming");
+
+    // Updating the category is reported to indexers
+    let signature = await program.methods.updateCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      "defi",
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc({ commitment: "confirmed" });
+    expect(await storedCategory()).to.equal("defi");
+    let event = (await transactionEvents(signature)).find(e => e.name == "AirdropCampaignUpdated");
+    expect(event.data.category).to.equal("defi");
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Reject airdropping from the creator ATA to itself", async () => {
+    let selfToken = await createMint(0, 1, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(selfToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMSLF";
+    let assets: any[] = [{
+      kind: { token: { mint: selfToken } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN((await getNow()) + MIN_START_DELAY + 5),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    await expectError(program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: creatorATA,
+      recipient: campaignCreator.publicKey,
+      mint: selfToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, campaignCreator.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc(), "SelfAirdrop");
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Withdraw fees only after the withdrawal delay", async () => {
+    // A platform of its own, so that the delay does not hold up the other tests
+    let timelockedPlatform = await platformAddress("timelocked");
+    await program.methods.initialize(
+      "timelocked",
+      new anchor.BN(50000000),
+      new anchor.BN(10000000),
+      new anchor.BN(1000000000),
+      0
+    ).accounts({
+      airdropPlatform: timelockedPlatform,
+      admin: admin.publicKey,
+      payer: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMTLK",
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(startingTime + 5),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform: timelockedPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    let adminAccounts = { admin: admin.publicKey, airdropPlatform: timelockedPlatform, systemProgram: anchor.web3.SystemProgram.programId };
+    let setWithdrawalDelay = (delay: number) => program.methods.setWithdrawalDelay(new anchor.BN(delay)).accounts(adminAccounts).signers([admin]).rpc();
+    let requestWithdrawal = () => program.methods.requestWithdrawal(new anchor.BN(50000000)).accounts(adminAccounts).signers([admin]).rpc();
+    let executeWithdrawal = () => program.methods.executeWithdrawal().accounts({ recipient: recipient.publicKey, ...adminAccounts }).signers([admin]).rpc();
+    await setWithdrawalDelay(5);
+    await expectError(setWithdrawalDelay(4), "InvalidWithdrawalDelay");
+    await expectError(program.methods.withdrawAirdropFee().accounts({ recipient: recipient.publicKey, ...adminAccounts }).signers([admin]).rpc(), "WithdrawalTimelocked");
+
+    // The campaign fee can only be withdrawn once the campaign ended and can no longer be refunded
+    await expectError(requestWithdrawal(), "InvalidWithdrawalAmount");
+    await waitUntil(startingTime + 6);
+
+    // A cancelled request cannot be executed
+    await requestWithdrawal();
+    await program.methods.cancelWithdrawal().accounts(adminAccounts).signers([admin]).rpc();
+    await expectError(executeWithdrawal(), "NoPendingWithdrawal");
+
+    // Executing is rejected before the delay and allowed after it
+    await requestWithdrawal();
+    await expectError(executeWithdrawal(), "WithdrawalTimelocked");
+    let requestedAt = (await program.account.airdropPlatform.fetch(timelockedPlatform)).withdrawalRequestedAt.toNumber();
+    await waitUntil(requestedAt + 5);
+    let recipientBalanceBefore = await connection.getBalance(recipient.publicKey);
+    await executeWithdrawal();
+    expect((await connection.getBalance(recipient.publicKey)) - recipientBalanceBefore).to.equal(50000000);
+    let platform = await program.account.airdropPlatform.fetch(timelockedPlatform);
+    expect(platform.accumulatedFees.toString()).to.equal("0");
+    expect(platform.pendingWithdrawal.toString()).to.equal("0");
+  });
+
+  it("Reject an airdrop from a campaign that does not exist", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMNON";
+    let creatorATA = await getAssociatedTokenAddress(token1.publicKey, campaignCreator.publicKey);
+    await expectError(program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: await getAssociatedTokenAddress(token1.publicKey, participant.publicKey),
+      recipient: participant.publicKey,
+      mint: token1.publicKey,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc(), "CampaignNotExists");
+  });
+
+  it("Airdrop every asset of a campaign in one instruction", async () => {
+    let mints = [await createMint(0, 3, campaignCreator.publicKey), await createMint(0, 7, campaignCreator.publicKey)];
+    let creatorATAs = await Promise.all(mints.map(mint => getAssociatedTokenAddress(mint, campaignCreator.publicKey)));
+    let participantATAs = await Promise.all(mints.map(mint => getAssociatedTokenAddress(mint, participant.publicKey)));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMALL";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [
+      { kind: { token: { mint: mints[0] } }, availableAmount: new anchor.BN(3), isNft: false, decimals: 0 },
+      { kind: { sol: {} }, availableAmount: new anchor.BN(200000000), isNft: false, decimals: 9 },
+      { kind: { token: { mint: mints[1] } }, availableAmount: new anchor.BN(7), isNft: false, decimals: 0 }
+    ];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...mints.map((mint, i) => createAssociatedTokenAccountInstruction(participant.publicKey, participantATAs[i], participant.publicKey, mint)),
+      createApproveInstruction(creatorATAs[0], airdropPlatform, campaignCreator.publicKey, 3),
+      createApproveInstruction(creatorATAs[1], airdropPlatform, campaignCreator.publicKey, 7)
+    ), [participant, campaignCreator]);
+    await waitUntil(startingTime);
+
+    // The creator ATA, the recipient ATA and the mint of every token asset
+    let tokenAccounts = [].concat(...mints.map((mint, i) => [
+      { pubkey: creatorATAs[i], isWritable: true, isSigner: false },
+      { pubkey: participantATAs[i], isWritable: true, isSigner: false },
+      { pubkey: mint, isWritable: false, isSigner: false }
+    ]));
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let airdropAllAssets = (amounts: number[]) => program.methods.airdropAllAssets(campaignId, amounts.map(amount => new anchor.BN(amount))).accounts({
+      recipient: participant.publicKey,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(tokenAccounts).signers([operator]).rpc();
+    await expectError(airdropAllAssets([3, 200000000]), "LengthsMismatch");
+
+    let balanceBefore = await connection.getBalance(participant.publicKey);
+    await airdropAllAssets([3, 200000000, 7]);
+    expect((await getAccount(connection, participantATAs[0])).amount.toString()).to.equal("3");
+    expect((await getAccount(connection, participantATAs[1])).amount.toString()).to.equal("7");
+    expect((await connection.getBalance(participant.publicKey)) - balanceBefore).to.equal(200000000);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+  });
+
+  it("Let a partner operator run a single campaign", async () => {
+    let partnerToken = await createMint(0, 1, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(partnerToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMPOP";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: partnerToken } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // The partner operator pays for the receipts it creates
+    let partner = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({ fromPubkey: defaultWallet.publicKey, toPubkey: partner.publicKey, lamports: 100000000 }),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+    ), [campaignCreator]);
+    await program.methods.setCampaignOperators(campaignId, campaignCreator.publicKey, [partner.publicKey]).accounts({
+      airdropPlatform,
+      authority: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    await waitUntil(startingTime);
+
+    let participantATA = await getAssociatedTokenAddress(partnerToken, participant.publicKey);
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let airdrop = (signer: anchor.web3.Keypair) => program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: partnerToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: signer.publicKey,
+      receipt,
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([signer]).rpc();
+
+    // The platform operators no longer airdrop this campaign, its own operator does
+    await expectError(airdrop(operator), "NotCampaignOperator");
+    await airdrop(partner);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("1");
+  });
+
+  it("Tell whether a recipient has claimed", async () => {
+    let claimedToken = await createMint(0, 1, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(claimedToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMHCL";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: claimedToken } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+    ), [campaignCreator]);
+
+    // The return data is a borsh-encoded bool
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let hasClaimed = async (): Promise<boolean> => {
+      let data = await viewReturnData(await program.methods.hasClaimed(campaignId, campaignCreator.publicKey, participant.publicKey).accounts({
+        receipt,
+        airdropPlatform
+      }).instruction());
+      return data.readUInt8(0) == 1;
+    };
+    expect(await hasClaimed()).to.equal(false);
+
+    await waitUntil(startingTime);
+    await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: await getAssociatedTokenAddress(claimedToken, participant.publicKey),
+      recipient: participant.publicKey,
+      mint: claimedToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    expect(await hasClaimed()).to.equal(true);
+  });
+
+  it("Take a percentage of the airdropped tokens as fee", async () => {
+    let cutToken = await createMint(0, 800, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(cutToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(cutToken, participant.publicKey);
+    let feeVault = await getAssociatedTokenAddress(cutToken, airdropPlatform, true);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMBPS";
+    let depositedId = "01BX5ZZKBKACTAV9WEVGEMMBPD";
+    let setFeeBps = (feeBps: number) => program.methods.setFeeBps(feeBps).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    await expectError(setFeeBps(10001), "InvalidFeeBps");
+    await setFeeBps(250);
+
+    // No SOL fee is charged in this mode
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let tokenAssets = (amounts: number[]): any[] => amounts.map(amount => ({
+      kind: { token: { mint: cutToken } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 0
+    }));
+    let createCampaign = (campaignId: string, assets: any[]) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let accumulatedFeesBefore = (await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees;
+    await createCampaign(campaignId, tokenAssets([400, 200, 120]));
+    await createCampaign(depositedId, tokenAssets([80]));
+    expect((await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees.toString()).to.equal(accumulatedFeesBefore.toString());
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, feeVault, airdropPlatform, cutToken),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 720)
+    ), [campaignCreator]);
+    await program.methods.depositAssets(depositedId).accounts({
+      campaignCreator: campaignCreator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts([
+      { pubkey: creatorATA, isWritable: true, isSigner: false },
+      { pubkey: cutToken, isWritable: false, isSigner: false },
+      { pubkey: feeVault, isWritable: true, isSigner: false }
+    ]).signers([campaignCreator]).rpc();
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let feeVaultAccount = { pubkey: feeVault, isWritable: true, isSigner: false };
+    let airdrop = (campaignId: string, source: PublicKey, remainingAccounts: any[]) => program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: source,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: cutToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(remainingAccounts).signers([operator]).rpc();
+    let tokenFees = async (): Promise<string> => {
+      let tokenFees: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).tokenFees;
+      return tokenFees.find(fee => fee.mint.equals(cutToken)).amount.toString();
+    };
+
+    // 2.5% of 400 goes to the platform, the rest to the recipient
+    await airdrop(campaignId, creatorATA, [feeVaultAccount]);
+    expect((await getAccount(connection, feeVault)).amount.toString()).to.equal("90");
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("390");
+    expect(await tokenFees()).to.equal("10");
+
+    // Airdropping several assets at once takes the same cut, the fee vault following the accounts of every asset
+    await program.methods.airdropAllAssets(campaignId, [0, 200, 0].map(amount => new anchor.BN(amount))).accounts({
+      recipient: participant.publicKey,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts([].concat(...[0, 1, 2].map(() => [
+      { pubkey: creatorATA, isWritable: true, isSigner: false },
+      { pubkey: participantATA, isWritable: true, isSigner: false },
+      { pubkey: cutToken, isWritable: false, isSigner: false },
+      feeVaultAccount
+    ]))).signers([operator]).rpc();
+    expect((await getAccount(connection, feeVault)).amount.toString()).to.equal("95");
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("585");
+
+    // So do claims authorized by an operator
+    let expiry = (await getNow()) + 60;
+    let message = Buffer.concat([
+      program.programId.toBuffer(),
+      airdropPlatform.toBuffer(),
+      campaignCreator.publicKey.toBuffer(),
+      Buffer.from(campaignId),
+      new anchor.BN(2).toArrayLike(Buffer, "le", 8),
+      participant.publicKey.toBuffer(),
+      new anchor.BN(120).toArrayLike(Buffer, "le", 8),
+      new anchor.BN(expiry).toArrayLike(Buffer, "le", 8)
+    ]);
+    let verifyIx = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({ privateKey: operator.secretKey, message });
+    await program.methods.claimWithAuthorization(
+      campaignId,
+      new anchor.BN(2),
+      new anchor.BN(120),
+      new anchor.BN(expiry),
+      Array.from(verifyIx.data.slice(48, 112))
+    ).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      mint: cutToken,
+      campaignCreator: campaignCreator.publicKey,
+      recipient: participant.publicKey,
+      receipt,
+      airdropPlatform,
+      instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts([feeVaultAccount]).preInstructions([verifyIx]).signers([participant]).rpc();
+    expect((await getAccount(connection, feeVault)).amount.toString()).to.equal("98");
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("702");
+    expect(await tokenFees()).to.equal("18");
+
+    // The cut of a deposited campaign stays in the vault it is airdropped from, recorded all the same
+    await airdrop(depositedId, feeVault, []);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("780");
+    expect((await getAccount(connection, feeVault)).amount.toString()).to.equal("20");
+    expect(await tokenFees()).to.equal("20");
+    await setFeeBps(0);
+  });
+
+  it("Reject an update that swaps a campaign mint", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMSWP";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let tokenAsset = (mint: PublicKey, decimals: number) => ({
+      kind: { token: { mint } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals
+    });
+    let assets: any[] = [tokenAsset(token1.publicKey, 9)];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    let swappedAssets: any[] = [tokenAsset(await createMint(0, 1, campaignCreator.publicKey), 0)];
+    await expectError(program.methods.updateCampaign(
+      campaignId,
+      swappedAssets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(swappedAssets)).signers([campaignCreator]).rpc(), "CannotChangeMints");
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Sign airdrops with the platform seeds", async () => {
+    let signedToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(signedToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(signedToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMSDS";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: signedToken } },
+      availableAmount: new anchor.BN(2),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 2)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    // The delegate is the PDA derived from the platform seeds, which must sign the transfer
+    expect(airdropPlatform.toBase58()).to.equal((await platformAddress("")).toBase58());
+    await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: signedToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("2");
+    expect((await getAccount(connection, creatorATA)).delegatedAmount.toString()).to.equal("0");
+  });
+
+  it("List the campaigns of a creator page by page", async () => {
+    let creators = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...creators.map(creator => anchor.web3.SystemProgram.transfer({ fromPubkey: defaultWallet.publicKey, toPubkey: creator.publicKey, lamports: 2000000000 }))
+    ));
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    let ownedCampaigns = [
+      [creators[0], "01BX5ZZKBKACTAV9WEVGEMMDB1"],
+      [creators[1], "01BX5ZZKBKACTAV9WEVGEMMDB2"],
+      [creators[0], "01BX5ZZKBKACTAV9WEVGEMMDB3"],
+      [creators[0], "01BX5ZZKBKACTAV9WEVGEMMDB4"]
+    ] as [anchor.web3.Keypair, string][];
+    for (let [creator, campaignId] of ownedCampaigns) {
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: creator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([creator]).rpc();
+    }
+
+    // The return data is a borsh-encoded vector: a u32 length followed by the campaigns
+    let getCreatorCampaigns = async (creator: PublicKey, offset: number, limit: number): Promise<string[]> => {
+      let data = await viewReturnData(await program.methods.getCreatorCampaigns(creator, offset, limit).accounts({ airdropPlatform }).instruction());
+      let campaignIds = [];
+      let position = 4;
+      for (let i = 0; i < data.readUInt32LE(0); i++) {
+        let campaign = program.coder.types.decode("AirdropCampaign", data.slice(position));
+        campaignIds.push(campaign.campaignId);
+        position += program.coder.types.encode("AirdropCampaign", campaign).length;
+      }
+      return campaignIds;
+    };
+    expect(await getCreatorCampaigns(creators[0].publicKey, 0, 2)).to.deep.equal(["01BX5ZZKBKACTAV9WEVGEMMDB1", "01BX5ZZKBKACTAV9WEVGEMMDB3"]);
+    expect(await getCreatorCampaigns(creators[0].publicKey, 2, 2)).to.deep.equal(["01BX5ZZKBKACTAV9WEVGEMMDB4"]);
+    expect(await getCreatorCampaigns(creators[1].publicKey, 0, 2)).to.deep.equal(["01BX5ZZKBKACTAV9WEVGEMMDB2"]);
+
+    for (let [creator, campaignId] of ownedCampaigns) {
+      await program.methods.cancelCampaign(campaignId).accounts({
+        airdropPlatform,
+        campaignCreator: creator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([creator]).rpc();
+    }
+  });
+
+  it("Waive the fee of exempt creators only", async () => {
+    let trustedCreator = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({ fromPubkey: defaultWallet.publicKey, toPubkey: trustedCreator.publicKey, lamports: 100000000 })
+    ));
+    await program.methods.setFeeExempt(trustedCreator.publicKey, true).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    let createCampaign = async (creator: anchor.web3.Keypair, campaignId: string): Promise<number> => {
+      let balanceBefore = await connection.getBalance(creator.publicKey);
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: creator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([creator]).rpc();
+      return balanceBefore - (await connection.getBalance(creator.publicKey));
+    };
+    let feePerAsset = (await program.account.airdropPlatform.fetch(airdropPlatform)).feePerAsset.toNumber();
+    expect(await createCampaign(trustedCreator, "01BX5ZZKBKACTAV9WEVGEMMEX1")).to.equal(0);
+    expect(await createCampaign(campaignCreator, "01BX5ZZKBKACTAV9WEVGEMMEX2")).to.equal(feePerAsset);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMEX1").airdropFee.toNumber()).to.equal(0);
+    expect(campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMEX2").airdropFee.toNumber()).to.equal(feePerAsset);
+
+    // Once the exemption is revoked, growing a campaign created while exempt is charged at the rate of its creation
+    await program.methods.setFeeExempt(trustedCreator.publicKey, false).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let balanceBefore = await connection.getBalance(trustedCreator.publicKey);
+    await program.methods.updateCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMEX1",
+      [...assets, ...assets],
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: trustedCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([trustedCreator]).rpc();
+    expect(balanceBefore - (await connection.getBalance(trustedCreator.publicKey))).to.equal(feePerAsset);
+    campaigns = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMEX1").airdropFee.toNumber()).to.equal(feePerAsset);
+
+    for (let [creator, campaignId] of [[trustedCreator, "01BX5ZZKBKACTAV9WEVGEMMEX1"], [campaignCreator, "01BX5ZZKBKACTAV9WEVGEMMEX2"]] as [anchor.web3.Keypair, string][]) {
+      await program.methods.cancelCampaign(campaignId).accounts({
+        airdropPlatform,
+        campaignCreator: creator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([creator]).rpc();
+    }
+  });
+
+  it("Resume an interrupted batch airdrop from the next index", async () => {
+    let batchToken = await createMint(0, 5, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(batchToken, campaignCreator.publicKey);
+    let recipients = [...Array(3).keys()].map(() => anchor.web3.Keypair.generate().publicKey);
+    let recipientATAs = await Promise.all(recipients.map(recipient => getAssociatedTokenAddress(batchToken, recipient)));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMBT1";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: batchToken } },
+      availableAmount: new anchor.BN(5),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...recipients.map((recipient, i) => createAssociatedTokenAccountInstruction(defaultWallet.publicKey, recipientATAs[i], recipient, batchToken)),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 5)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let batchAirdrop = (startIndex: number, batch: PublicKey[]) => program.methods.batchAirdrop(
+      campaignId,
+      new anchor.BN(0),
+      new anchor.BN(startIndex),
+      batch.map(() => new anchor.BN(1))
+    ).accounts({
+      creatorAta: creatorATA,
+      mint: batchToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts(batch.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }))).signers([operator]).rpc();
+    let progress = async (): Promise<[number, number]> => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      let campaign = campaigns.find(c => c.campaignId == campaignId);
+      return [campaign.distributedCount.toNumber(), campaign.nextIndex.toNumber()];
+    };
+
+    // The first batch is interrupted after two recipients, the operator resumes from the next index
+    await batchAirdrop(0, recipientATAs.slice(0, 2));
+    expect(await progress()).to.deep.equal([2, 2]);
+    await expectError(batchAirdrop(0, recipientATAs.slice(2)), "BatchOutOfOrder");
+    await batchAirdrop(2, recipientATAs.slice(2));
+    expect(await progress()).to.deep.equal([3, 3]);
+    for (let recipientATA of recipientATAs) {
+      expect((await getAccount(connection, recipientATA)).amount.toString()).to.equal("1");
+    }
+
+    // Drain the campaign so that it does not linger in the platform account
+    await batchAirdrop(3, recipientATAs.slice(0, 2));
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+  });
+
+  it("Reject campaigns without assets", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMNA1";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    let createCampaign = (campaignAssets: any[]) => program.methods.createAirdropCampaign(
+      campaignId,
+      campaignAssets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(campaignAssets)).signers([campaignCreator]).rpc();
+    await expectError(createCampaign([]), "NoAssets");
+    await createCampaign(assets);
+    await expectError(program.methods.updateCampaign(
+      campaignId,
+      [],
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc(), "NoAssets");
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Emit a standard airdrop event alongside the bespoke one", async () => {
+    let indexedToken = await createMint(0, 3, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(indexedToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(indexedToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMSTD";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: indexedToken } },
+      availableAmount: new anchor.BN(3),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 3)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let signature = await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: indexedToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc({ commitment: "confirmed" });
+    let events = await transactionEvents(signature);
+    let executed = events.find(e => e.name == "AirdropExecuted");
+    expect(executed?.data?.amount?.toString()).to.equal("3");
+    let standard = events.find(e => e.name == "StandardAirdropEvent");
+    expect(standard?.data?.programId?.toString()).to.equal(program.programId.toString());
+    expect(standard?.data?.campaign).to.equal(campaignId);
+    expect(standard?.data?.recipient?.toString()).to.equal(participant.publicKey.toString());
+    expect(standard?.data?.mint?.toString()).to.equal(indexedToken.toString());
+    expect(standard?.data?.amount?.toString()).to.equal("3");
+    expect(standard?.data?.timestamp?.toNumber()).to.be.at.least(startingTime);
+  });
+
+  it("Split an asset between recipients by weight", async () => {
+    let weightedToken = await createMint(0, 1000, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(weightedToken, campaignCreator.publicKey);
+    let recipients = [...Array(3).keys()].map(() => anchor.web3.Keypair.generate().publicKey);
+    let recipientATAs = await Promise.all(recipients.map(recipient => getAssociatedTokenAddress(weightedToken, recipient)));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMWGT";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: weightedToken } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...recipients.map((recipient, i) => createAssociatedTokenAccountInstruction(defaultWallet.publicKey, recipientATAs[i], recipient, weightedToken)),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1000)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let airdropWeighted = (weights: number[]) => program.methods.airdropWeighted(
+      campaignId,
+      new anchor.BN(0),
+      recipients,
+      weights.map(weight => new anchor.BN(weight))
+    ).accounts({
+      creatorAta: creatorATA,
+      mint: weightedToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts(recipientATAs.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }))).signers([operator]).rpc();
+    await expectError(airdropWeighted([1, 1]), "LengthsMismatch");
+    await airdropWeighted([1, 1, 2]);
+    let shares = await Promise.all(recipientATAs.map(async recipientATA => (await getAccount(connection, recipientATA)).amount.toString()));
+    expect(shares).to.deep.equal(["250", "250", "500"]);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+  });
+
+  it("Fund the platform account from a wallet other than the admin", async () => {
+    let fundedPlatform = await platformAddress("funded");
+    let adminBalanceBefore = await connection.getBalance(admin.publicKey);
+    let payerBalanceBefore = await connection.getBalance(defaultWallet.publicKey);
+    await program.methods.initialize(
+      "funded",
+      new anchor.BN(50000000),
+      new anchor.BN(10000000),
+      new anchor.BN(1000000000),
+      0
+    ).accounts({
+      airdropPlatform: fundedPlatform,
+      admin: admin.publicKey,
+      payer: defaultWallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let platform = await program.account.airdropPlatform.fetch(fundedPlatform);
+    expect(platform.admin.toString()).to.equal(admin.publicKey.toString());
+    expect(await connection.getBalance(admin.publicKey)).to.equal(adminBalanceBefore);
+    let rentExemptMinimum = await connection.getMinimumBalanceForRentExemption(10240);
+    expect(payerBalanceBefore - (await connection.getBalance(defaultWallet.publicKey))).to.be.at.least(rentExemptMinimum);
+  });
+
+  it("Keep an audit trail of fee withdrawals", async () => {
+    let recipients = [...Array(3).keys()].map(() => anchor.web3.Keypair.generate().publicKey);
+    let accumulatedFees = (await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees;
+    for (let withdrawalRecipient of recipients) {
+      await program.methods.withdrawAirdropFee().accounts({
+        recipient: withdrawalRecipient,
+        admin: admin.publicKey,
+        airdropPlatform,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([admin]).rpc();
+    }
+
+    // The return data is a borsh-encoded vector: a u32 length followed by (i64, public key, u64) entries
+    let data = await viewReturnData(await program.methods.getRecentWithdrawals().accounts({ airdropPlatform }).instruction());
+    let withdrawals = [...Array(data.readUInt32LE(0)).keys()].map(i => ({
+      timestamp: Number(data.readBigInt64LE(4 + 48 * i)),
+      recipient: new PublicKey(data.slice(12 + 48 * i, 44 + 48 * i)),
+      amount: data.readBigUInt64LE(44 + 48 * i).toString()
+    }));
+    expect(withdrawals.length).to.equal(3);
+    expect(withdrawals.map(w => w.recipient.toString())).to.deep.equal(recipients.map(r => r.toString()));
+    let withdrawnFees = accumulatedFees.sub((await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees);
+    expect(withdrawals.map(w => w.amount)).to.deep.equal([withdrawnFees.toString(), "0", "0"]);
+    expect(withdrawals[0].timestamp).to.be.greaterThan(0);
+  });
+
+  it("Reject airdrops of a depleted asset", async () => {
+    let depletedToken = await createMint(0, 3, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(depletedToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(depletedToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMDPL";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [1, 2].map(amount => ({
+      kind: { token: { mint: depletedToken } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 0
+    }));
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 3)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let airdrop = (assetIndex: number) => program.methods.airdrop(campaignId, new anchor.BN(assetIndex)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: depletedToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    await airdrop(1);
+    await expectError(airdrop(1), "AssetDepleted");
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("2");
+    await airdrop(0);
+  });
+
+  it("Require the creator to co-sign each airdrop", async () => {
+    let cosignedToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(cosignedToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(cosignedToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMCSG";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: cosignedToken } },
+      availableAmount: new anchor.BN(2),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await program.methods.setRequireCreatorCosign(campaignId, true).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 2)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    // The creator is not a signer in the IDL, so it is flagged as one by hand to co-sign
+    let airdrop = async (cosign: boolean) => {
+      let instruction = await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+        creatorAta: creatorATA,
+        recipientAta: participantATA,
+        recipient: participant.publicKey,
+        mint: cosignedToken,
+        campaignCreator: campaignCreator.publicKey,
+        operator: operator.publicKey,
+        receipt: await receiptAddress(campaignId, participant.publicKey),
+        airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).instruction();
+      instruction.keys.find(key => key.pubkey.equals(campaignCreator.publicKey)).isSigner = cosign;
+      return provider.sendAndConfirm(new anchor.web3.Transaction().add(instruction), cosign ? [operator, campaignCreator] : [operator]);
+    };
+    await expectError(airdrop(false), "CreatorCosignRequired");
+    await airdrop(true);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("2");
+  });
+
+  it("Leave every balance untouched when a transfer fails", async () => {
+    let rollbackToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(rollbackToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(rollbackToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMRBK";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [
+      { kind: { sol: {} }, availableAmount: new anchor.BN(100000000), isNft: false, decimals: 9 },
+      { kind: { token: { mint: rollbackToken } }, availableAmount: new anchor.BN(2), isNft: false, decimals: 0 }
+    ];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // The creator only delegates half of the tokens, so the token transfer fails
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(participant.publicKey, participantATA, participant.publicKey, rollbackToken),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+    ), [participant, campaignCreator]);
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let airdropAllAssets = () => program.methods.airdropAllAssets(campaignId, [new anchor.BN(100000000), new anchor.BN(2)]).accounts({
+      recipient: participant.publicKey,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts([
+      { pubkey: creatorATA, isWritable: true, isSigner: false },
+      { pubkey: participantATA, isWritable: true, isSigner: false },
+      { pubkey: rollbackToken, isWritable: false, isSigner: false }
+    ]).signers([operator]).rpc();
+    let balances = async () => [
+      await connection.getBalance(participant.publicKey),
+      await connection.getBalance(airdropPlatform),
+      (await getAccount(connection, participantATA)).amount.toString(),
+      (await getAccount(connection, creatorATA)).amount.toString()
+    ];
+    let balancesBefore = await balances();
+    await expectError(airdropAllAssets(), "insufficient funds");
+    expect(await balances()).to.deep.equal(balancesBefore);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == campaignId);
+    expect(campaign.assets.map(asset => asset.availableAmount.toString())).to.deep.equal(["100000000", "2"]);
+
+    // Once the whole amount is delegated, the same airdrop goes through
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 2)
+    ), [campaignCreator]);
+    await airdropAllAssets();
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("2");
+  });
+
+  it("Enforce the minimum campaign duration at the boundary", async () => {
+    let setMinCampaignDuration = (minCampaignDuration: number) => program.methods.setMinCampaignDuration(new anchor.BN(minCampaignDuration)).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    await setMinCampaignDuration(3600);
+
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMDUR";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    let createCampaign = (endingTime: number) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(endingTime),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let cancelCampaign = () => program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    await expectError(createCampaign(startingTime + 3599), "DurationTooShort");
+    await createCampaign(startingTime + 3600);
+    await cancelCampaign();
+
+    // Campaigns without an ending time are not affected, and zero disables the minimum
+    await createCampaign(0);
+    await cancelCampaign();
+    await setMinCampaignDuration(0);
+    await createCampaign(startingTime + 1);
+    await cancelCampaign();
+  });
+
+  it("Airdrop by mint after an update reordered the assets", async () => {
+    let mints = [await createMint(0, 2, campaignCreator.publicKey), await createMint(0, 3, campaignCreator.publicKey)];
+    let creatorATAs = await Promise.all(mints.map(mint => getAssociatedTokenAddress(mint, campaignCreator.publicKey)));
+    let participantATAs = await Promise.all(mints.map(mint => getAssociatedTokenAddress(mint, participant.publicKey)));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMBYM";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = mints.map((mint, i) => ({
+      kind: { token: { mint } },
+      availableAmount: new anchor.BN(i + 2),
+      isNft: false,
+      decimals: 0
+    }));
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    let reorderedAssets = [...assets].reverse();
+    await program.methods.updateCampaign(
+      campaignId,
+      reorderedAssets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(reorderedAssets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATAs[0], airdropPlatform, campaignCreator.publicKey, 2),
+      createApproveInstruction(creatorATAs[1], airdropPlatform, campaignCreator.publicKey, 3)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let airdropByMint = async (mint: PublicKey) => program.methods.airdropByMint(campaignId).accounts({
+      creatorAta: await getAssociatedTokenAddress(mint, campaignCreator.publicKey),
+      recipientAta: await getAssociatedTokenAddress(mint, participant.publicKey),
+      recipient: participant.publicKey,
+      mint,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    await airdropByMint(mints[0]);
+    expect((await getAccount(connection, participantATAs[0])).amount.toString()).to.equal("2");
+    await expectError(airdropByMint(token1.publicKey), "AssetAddressMismatch");
+    await airdropByMint(mints[1]);
+    expect((await getAccount(connection, participantATAs[1])).amount.toString()).to.equal("3");
+  });
+
+  it("Let different creators reuse the same campaign ID", async () => {
+    let otherCreator = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({ fromPubkey: defaultWallet.publicKey, toPubkey: otherCreator.publicKey, lamports: 100000000 })
+    ));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMSID";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    let createCampaign = (creator: anchor.web3.Keypair) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: creator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([creator]).rpc();
+    let cancelCampaign = (creator: anchor.web3.Keypair) => program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: creator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([creator]).rpc();
+    let fetchCreators = async (): Promise<string[]> => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.filter(c => c.campaignId == campaignId).map(c => c.creator.toString()).sort();
+    };
+
+    // The ID only has to be unique among the campaigns of a single creator
+    await createCampaign(campaignCreator);
+    await createCampaign(otherCreator);
+    await expectError(createCampaign(otherCreator), "CampaignAlreadyCreated");
+    expect(await fetchCreators()).to.deep.equal([campaignCreator.publicKey.toString(), otherCreator.publicKey.toString()].sort());
+
+    // Cancelling one campaign leaves the other creator's campaign untouched
+    await cancelCampaign(otherCreator);
+    expect(await fetchCreators()).to.deep.equal([campaignCreator.publicKey.toString()]);
+    await cancelCampaign(campaignCreator);
+    expect(await fetchCreators()).to.deep.equal([]);
+  });
+
+  it("Charge recipients the platform claim fee", async () => {
+    let feeToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(feeToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(feeToken, participant.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMCFE";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: feeToken } },
+      availableAmount: new anchor.BN(2),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(participant.publicKey, participantATA, participant.publicKey, feeToken),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 2)
+    ), [participant, campaignCreator]);
+    await waitUntil(startingTime);
+
+    let setClaimFee = (claimFee: number) => program.methods.setClaimFee(new anchor.BN(claimFee)).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let claim = (expiry: number) => {
+      let message = Buffer.concat([
+        program.programId.toBuffer(),
+        airdropPlatform.toBuffer(),
+        campaignCreator.publicKey.toBuffer(),
+        Buffer.from(campaignId),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+        participant.publicKey.toBuffer(),
+        new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+        new anchor.BN(expiry).toArrayLike(Buffer, "le", 8)
+      ]);
+      let verifyIx = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({ privateKey: operator.secretKey, message });
+      return program.methods.claimWithAuthorization(
+        campaignId,
+        new anchor.BN(0),
+        new anchor.BN(1),
+        new anchor.BN(expiry),
+        Array.from(verifyIx.data.slice(48, 112))
+      ).accounts({
+        creatorAta: creatorATA,
+        recipientAta: participantATA,
+        mint: feeToken,
+        campaignCreator: campaignCreator.publicKey,
+        recipient: participant.publicKey,
+        receipt,
+        airdropPlatform,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).preInstructions([verifyIx]).signers([participant]).rpc();
+    };
+    let accumulatedFees = async () => (await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees.toNumber();
+
+    // A nonzero claim fee moves from the recipient to the platform fees
+    let claimFee = 1000000;
+    await setClaimFee(claimFee);
+    let feesBefore = await accumulatedFees();
+    await claim(startingTime + 60);
+    expect(await accumulatedFees()).to.equal(feesBefore + claimFee);
+
+    // A zero claim fee leaves the recipient balance untouched
+    await setClaimFee(0);
+    let balanceBefore = await connection.getBalance(participant.publicKey);
+    await claim(startingTime + 61);
+    expect(await accumulatedFees()).to.equal(feesBefore + claimFee);
+    expect(await connection.getBalance(participant.publicKey)).to.equal(balanceBefore);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("2");
+  });
+
+  it("Airdrop out of the vault the creator deposited into", async () => {
+    let depositToken = await createMint(0, 3, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(depositToken, campaignCreator.publicKey);
+    let participantATA = await getAssociatedTokenAddress(depositToken, participant.publicKey);
+    let vault = await getAssociatedTokenAddress(depositToken, airdropPlatform, true);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMDEP";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [2, 1].map(amount => ({
+      kind: { token: { mint: depositToken } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 0
+    }));
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(startingTime + 5),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, vault, airdropPlatform, depositToken)
+    ));
+
+    // Every token asset moves from the creator ATA into the platform vault of its mint, no delegation needed
+    let depositAccounts = [
+      { pubkey: creatorATA, isWritable: true, isSigner: false },
+      { pubkey: depositToken, isWritable: false, isSigner: false },
+      { pubkey: vault, isWritable: true, isSigner: false }
+    ];
+    await program.methods.depositAssets(campaignId).accounts({
+      campaignCreator: campaignCreator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts([...depositAccounts, ...depositAccounts]).signers([campaignCreator]).rpc();
+    expect((await getAccount(connection, creatorATA)).amount.toString()).to.equal("0");
+    expect((await getAccount(connection, vault)).amount.toString()).to.equal("3");
+
+    // Airdrops of a deposited campaign come out of the vault
+    await waitUntil(startingTime);
+    await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: vault,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: depositToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, participant.publicKey),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("2");
+    expect((await getAccount(connection, vault)).amount.toString()).to.equal("1");
+
+    // The leftover stays locked while the campaign runs, and goes back to the creator once it ended
+    let withdrawUnusedDeposit = () => program.methods.withdrawUnusedDeposit(campaignId, new anchor.BN(1)).accounts({
+      creatorAta: creatorATA,
+      vault,
+      mint: depositToken,
+      campaignCreator: campaignCreator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).signers([campaignCreator]).rpc();
+    await expectError(withdrawUnusedDeposit(), "DepositLocked");
+    await waitUntil(startingTime + 6);
+    await withdrawUnusedDeposit();
+    expect((await getAccount(connection, creatorATA)).amount.toString()).to.equal("1");
+    expect((await getAccount(connection, vault)).amount.toString()).to.equal("0");
+
+    await program.methods.reclaimCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Log the compute usage of batch airdrops in debug mode", async () => {
+    let debugToken = await createMint(0, 2, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(debugToken, campaignCreator.publicKey);
+    let recipients = [...Array(2).keys()].map(() => anchor.web3.Keypair.generate().publicKey);
+    let recipientATAs = await Promise.all(recipients.map(recipient => getAssociatedTokenAddress(debugToken, recipient)));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMDBG";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: debugToken } },
+      availableAmount: new anchor.BN(2),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...recipients.map((recipient, i) => createAssociatedTokenAccountInstruction(defaultWallet.publicKey, recipientATAs[i], recipient, debugToken)),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 2)
+    ), [campaignCreator]);
+    let setDebugLogging = (debugLogging: boolean) => program.methods.setDebugLogging(debugLogging).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    await setDebugLogging(true);
+    await waitUntil(startingTime);
+
+    // The number of recipients is logged between two compute unit readings
+    let signature = await program.methods.batchAirdrop(
+      campaignId,
+      new anchor.BN(0),
+      new anchor.BN(0),
+      recipientATAs.map(() => new anchor.BN(1))
+    ).accounts({
+      creatorAta: creatorATA,
+      mint: debugToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts(recipientATAs.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }))).signers([operator]).rpc({ commitment: "confirmed" });
+    let logs = (await connection.getTransaction(signature, { commitment: "confirmed" })).meta.logMessages;
+    expect(logs.some(log => log.includes("Batch airdrop: 2 recipients processed"))).to.equal(true);
+    expect(logs.filter(log => log.includes("units remaining")).length).to.equal(2);
+    await setDebugLogging(false);
+  });
+
+  it("Reject nonsensical operators", async () => {
+    let setOperators = (operators: PublicKey[]) => program.methods.setOperators(operators, operators.map(() => true)).accounts({
+      airdropPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let operatorsBefore = (await program.account.airdropPlatform.fetch(airdropPlatform)).operators.length;
+
+    // Neither the default pubkey nor the platform itself can ever sign
+    await expectError(setOperators([PublicKey.default]), "InvalidOperator");
+    await expectError(setOperators([airdropPlatform]), "InvalidOperator");
+    expect((await program.account.airdropPlatform.fetch(airdropPlatform)).operators.length).to.equal(operatorsBefore);
+  });
+
+  it("List the active campaign ids page by page", async () => {
+    let creator = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({ fromPubkey: defaultWallet.publicKey, toPubkey: creator.publicKey, lamports: 2000000000 })
+    ));
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    let campaignIds = [...Array(5).keys()].map(i => `01BX5ZZKBKACTAV9WEVGEMMID${i}`);
+    for (let campaignId of campaignIds) {
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: creator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([creator]).rpc();
+    }
+
+    // The return data is a borsh-encoded vector: a u32 length followed by length-prefixed strings
+    let listCampaignIds = async (offset: number, limit: number): Promise<string[]> => {
+      let data = await viewReturnData(await program.methods.listCampaignIds(offset, limit).accounts({ airdropPlatform }).instruction());
+      let campaignIds = [];
+      let position = 4;
+      for (let i = 0; i < data.readUInt32LE(0); i++) {
+        let length = data.readUInt32LE(position);
+        campaignIds.push(data.slice(position + 4, position + 4 + length).toString());
+        position += 4 + length;
+      }
+      return campaignIds;
+    };
+
+    // Campaigns of a single creator are listed next to each other
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let offset = campaigns.filter(c => !c.completed).findIndex(c => c.campaignId == campaignIds[0]);
+    expect(await listCampaignIds(offset, 3)).to.deep.equal(campaignIds.slice(0, 3));
+    expect(await listCampaignIds(offset + 3, 2)).to.deep.equal(campaignIds.slice(3));
+
+    for (let campaignId of campaignIds) {
+      await program.methods.cancelCampaign(campaignId).accounts({
+        airdropPlatform,
+        campaignCreator: creator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([creator]).rpc();
+    }
+  });
+
+  it("Only airdrop to recipients holding enough tokens", async () => {
+    let rewardToken = await createMint(0, 1, campaignCreator.publicKey);
+    let holdingToken = await createMint(0, 5, participant.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(rewardToken, campaignCreator.publicKey);
+    let newcomer = anchor.web3.Keypair.generate().publicKey;
+    let holdingATAs = {
+      [participant.publicKey.toString()]: await getAssociatedTokenAddress(holdingToken, participant.publicKey),
+      [newcomer.toString()]: await getAssociatedTokenAddress(holdingToken, newcomer)
+    };
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, holdingATAs[newcomer.toString()], newcomer, holdingToken),
+      createMintToInstruction(holdingToken, holdingATAs[newcomer.toString()], defaultWallet.publicKey, 1)
+    ));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMHLD";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: rewardToken } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await program.methods.setMinHolding(campaignId, holdingToken, new anchor.BN(5)).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    // The recipient's holding account comes last in the remaining accounts
+    let airdrop = async (recipient: PublicKey) => program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: await getAssociatedTokenAddress(rewardToken, recipient),
+      recipient,
+      mint: rewardToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      receipt: await receiptAddress(campaignId, recipient),
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts([{ pubkey: holdingATAs[recipient.toString()], isWritable: false, isSigner: false }]).signers([operator]).rpc();
+    await expectError(airdrop(newcomer), "IneligibleHolder");
+    await airdrop(participant.publicKey);
+    let participantATA = await getAssociatedTokenAddress(rewardToken, participant.publicKey);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("1");
+  });
+
+  it("Keep the fee of a campaign when the platform fee changes", async () => {
+    let setFeePerAsset = (fee: number) => program.methods.setFeePerAsset(new anchor.BN(fee)).accounts({
+      operator: operator.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMFEE";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 60;
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await setFeePerAsset(200000000);
+
+    // Only the schedule changes, so the creator pays nothing more despite the higher platform fee
+    let balanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    await program.methods.updateCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime + 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    expect(await connection.getBalance(campaignCreator.publicKey)).to.equal(balanceBefore);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == campaignId);
+    expect(campaign.airdropFee.toString()).to.equal("100000000");
+    expect(campaign.feePerAsset.toString()).to.equal("100000000");
+
+    // Restore the fee for the following tests
+    await setFeePerAsset(100000000);
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Purge completed campaigns once they are old enough", async () => {
+    let setKeepDrainedCampaigns = (keep: boolean) => program.methods.setKeepDrainedCampaigns(keep).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let purgeToken = await createMint(0, 3, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(purgeToken, campaignCreator.publicKey);
+    let campaignIds = ["01BX5ZZKBKACTAV9WEVGEMMPG1", "01BX5ZZKBKACTAV9WEVGEMMPG2", "01BX5ZZKBKACTAV9WEVGEMMPG3"];
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: purgeToken } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    for (let campaignId of campaignIds) {
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        // The last campaign has no ending time
+        new anchor.BN(campaignId == campaignIds[2] ? 0 : startingTime + 3),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    }
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 3)
+    ), [campaignCreator]);
+
+    // Drain both campaigns while drained campaigns are kept as records
+    await setKeepDrainedCampaigns(true);
+    await waitUntil(startingTime);
+    for (let campaignId of campaignIds) {
+      await program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+        creatorAta: creatorATA,
+        recipientAta: await getAssociatedTokenAddress(purgeToken, participant.publicKey),
+        recipient: participant.publicKey,
+        mint: purgeToken,
+        campaignCreator: campaignCreator.publicKey,
+        operator: operator.publicKey,
+        receipt: await receiptAddress(campaignId, participant.publicKey),
+        airdropPlatform,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([operator]).rpc();
+    }
+    await setKeepDrainedCampaigns(false);
+
+    let purgeCompleted = (olderThan: number) => program.methods.purgeCompleted(new anchor.BN(olderThan)).accounts({
+      admin: admin.publicKey,
+      airdropPlatform
+    }).signers([admin]).rpc({ commitment: "confirmed" });
+    let remainingIds = async (): Promise<string[]> => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.map(c => c.campaignId).filter(campaignId => campaignIds.includes(campaignId));
+    };
+
+    // Campaigns completed recently are kept, even without an ending time, older ones are removed
+    await expectError(program.methods.purgeCompleted(new anchor.BN(0)).accounts({
+      admin: participant.publicKey,
+      airdropPlatform
+    }).signers([participant]).rpc(), "ConstraintRaw");
+    await purgeCompleted(3600);
+    expect(await remainingIds()).to.deep.equal(campaignIds);
+    await waitUntil((await getNow()) + 2);
+    let event = (await transactionEvents(await purgeCompleted(0))).find(e => e.name == "CampaignsPurged");
+    expect(event.data.count).to.be.at.least(3);
+    expect(await remainingIds()).to.deep.equal([]);
+  });
+
+  it("Only let allowed distributors among the platform operators airdrop", async () => {
+    let distributors = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+    let [distributor, outsider] = distributors;
+    let setOperators = (operators: PublicKey[], isOperator: boolean) => program.methods.setOperators(operators, operators.map(() => isOperator)).accounts({
+      airdropPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...distributors.map(d => anchor.web3.SystemProgram.transfer({ fromPubkey: defaultWallet.publicKey, toPubkey: d.publicKey, lamports: 100000000 }))
+    ));
+    await setOperators([distributor.publicKey], true);
+
+    let distributorToken = await createMint(0, 1, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(distributorToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMDST";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: distributorToken } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await program.methods.setAllowedDistributors(campaignId, distributors.map(d => d.publicKey)).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 1)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    let receipt = await receiptAddress(campaignId, participant.publicKey);
+    let participantATA = await getAssociatedTokenAddress(distributorToken, participant.publicKey);
+    let airdrop = (signer: anchor.web3.Keypair) => program.methods.airdrop(campaignId, new anchor.BN(0)).accounts({
+      creatorAta: creatorATA,
+      recipientAta: participantATA,
+      recipient: participant.publicKey,
+      mint: distributorToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: signer.publicKey,
+      receipt,
+      airdropPlatform,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([signer]).rpc();
+
+    // A platform operator off the list and a listed wallet that is no platform operator are both rejected
+    await expectError(airdrop(operator), "NotCampaignOperator");
+    await expectError(airdrop(outsider), "NotCampaignOperator");
+    await airdrop(distributor);
+    expect((await getAccount(connection, participantATA)).amount.toString()).to.equal("1");
+
+    await setOperators([distributor.publicKey], false);
+  });
+
+  it("Summarize a batch airdrop in a single event", async () => {
+    let summaryToken = await createMint(0, 10, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(summaryToken, campaignCreator.publicKey);
+    let recipients = [...Array(4).keys()].map(() => anchor.web3.Keypair.generate().publicKey);
+    let recipientATAs = await Promise.all(recipients.map(recipient => getAssociatedTokenAddress(summaryToken, recipient)));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMSUM";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: summaryToken } },
+      availableAmount: new anchor.BN(10),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...recipients.map((recipient, i) => createAssociatedTokenAccountInstruction(defaultWallet.publicKey, recipientATAs[i], recipient, summaryToken)),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 10)
+    ), [campaignCreator]);
+    await waitUntil(startingTime);
+
+    // Four recipients receiving 1 to 4 tokens drain the campaign in one batch
+    let signature = await program.methods.batchAirdrop(
+      campaignId,
+      new anchor.BN(0),
+      new anchor.BN(0),
+      [1, 2, 3, 4].map(amount => new anchor.BN(amount))
+    ).accounts({
+      creatorAta: creatorATA,
+      mint: summaryToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts(recipientATAs.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }))).signers([operator]).rpc({ commitment: "confirmed" });
+    let summaries = (await transactionEvents(signature)).filter(e => e.name == "BatchAirdropCompleted");
+    expect(summaries.length).to.equal(1);
+    expect(summaries[0].data.campaignId).to.equal(campaignId);
+    expect(summaries[0].data.recipientCount.toNumber()).to.equal(4);
+    expect(summaries[0].data.totalAmount.toNumber()).to.equal(10);
+  });
+
+  it("Reject batch airdrops with too many accounts", async () => {
+    let capToken = await createMint(0, 17, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(capToken, campaignCreator.publicKey);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMCAP";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: capToken } },
+      availableAmount: new anchor.BN(17),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // The account list is checked before anything else, 17 accounts are one too many
+    let recipientATAs = [...Array(17).keys()].map(() => anchor.web3.Keypair.generate().publicKey);
+    await expectError(program.methods.batchAirdrop(
+      campaignId,
+      new anchor.BN(0),
+      new anchor.BN(0),
+      recipientATAs.map(() => new anchor.BN(1))
+    ).accounts({
+      creatorAta: creatorATA,
+      mint: capToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts(recipientATAs.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }))).signers([operator]).rpc(), "BatchTooLarge");
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Refund the original fee when cancelling after a fee change", async () => {
+    let setFeePerAsset = (fee: number) => program.methods.setFeePerAsset(new anchor.BN(fee)).accounts({
+      operator: operator.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMRFE";
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }, {
+      kind: { token: { mint: token2.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN((await getNow()) + MIN_START_DELAY + 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // The new fee only applies to campaigns created afterwards
+    await setFeePerAsset(300000000);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.find(c => c.campaignId == campaignId).airdropFee.toString()).to.equal("200000000");
+
+    // Cancelling refunds exactly what was paid at creation
+    let balanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    let signature = await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc({ commitment: "confirmed" });
+    let event = (await transactionEvents(signature)).find(e => e.name == "CampaignCancelled");
+    expect(event.data.refundedFee.toString()).to.equal("200000000");
+    expect((await connection.getBalance(campaignCreator.publicKey)) - balanceBefore).to.equal(200000000);
+
+    // Restore the fee for the following tests
+    await setFeePerAsset(100000000);
+  });
+
+  it("Only accept allowlisted mints in allowlist mode", async () => {
+    let approvedToken = await createMint(0, 1, campaignCreator.publicKey);
+    let unapprovedToken = await createMint(0, 1, campaignCreator.publicKey);
+    let setAllowlistEnabled = (enabled: boolean) => program.methods.setAllowlistEnabled(enabled).accounts({
+      airdropPlatform,
+      operator: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let setMintAllowed = (mint: anchor.web3.PublicKey, allowed: boolean) => program.methods.setMintAllowed(mint, allowed).accounts({
+      airdropPlatform,
+      operator: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+    let startingTime = (await getNow()) + MIN_START_DELAY + 60;
+    let createCampaign = (campaignId: string, mint: anchor.web3.PublicKey) => {
+      let assets: any[] = [{
+        kind: { token: { mint } },
+        availableAmount: new anchor.BN(1),
+        isNft: false,
+        decimals: 0
+      }];
+      return program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    };
+    await setAllowlistEnabled(true);
+    await setMintAllowed(approvedToken, true);
+
+    // Only the approved mint can be airdropped while the allowlist is enabled
+    await expectError(createCampaign("01BX5ZZKBKACTAV9WEVGEMMAL1", unapprovedToken), "MintNotAllowed");
+    await createCampaign("01BX5ZZKBKACTAV9WEVGEMMAL2", approvedToken);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMAL2")).to.equal(true);
+
+    // Restore the default mode for the following tests
+    await setMintAllowed(approvedToken, false);
+    await setAllowlistEnabled(false);
+    await program.methods.cancelCampaign("01BX5ZZKBKACTAV9WEVGEMMAL2").accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Keep asset indices stable across an update", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMIDX";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 60;
+    let tokenAsset = (amount: number) => ({
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 9
+    });
+    let assets: any[] = [tokenAsset(1)];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // The token asset keeps index 0 although it is listed last, the new SOL asset is appended
+    let updatedAssets: any[] = [{
+      kind: { sol: {} },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }, tokenAsset(2)];
+    await program.methods.updateCampaign(
+      campaignId,
+      updatedAssets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(updatedAssets)).signers([campaignCreator]).rpc();
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let campaign = campaigns.find(c => c.campaignId == campaignId);
+    expect(campaign.assets[0].kind.token.mint.toBase58()).to.equal(token1.publicKey.toBase58());
+    expect(campaign.assets[0].availableAmount.toString()).to.equal("2");
+    expect(campaign.assets[1].kind.sol).to.not.be.undefined;
+    expect(campaign.assets[1].availableAmount.toString()).to.equal("1000");
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Distribute an uploaded recipient list chunk by chunk", async () => {
+    let listToken = await createMint(0, 6, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(listToken, campaignCreator.publicKey);
+    let recipients = [...Array(4).keys()].map(() => anchor.web3.Keypair.generate().publicKey);
+    let recipientATAs = await Promise.all(recipients.map(recipient => getAssociatedTokenAddress(listToken, recipient)));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMLST";
+    let [recipientList] = await PublicKey.findProgramAddress(
+      [Buffer.from("recipients"), airdropPlatform.toBuffer(), campaignCreator.publicKey.toBuffer(), Buffer.from(campaignId)],
+      program.programId
+    );
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: listToken } },
+      availableAmount: new anchor.BN(6),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...recipients.map((recipient, i) => createAssociatedTokenAccountInstruction(defaultWallet.publicKey, recipientATAs[i], recipient, listToken)),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 6)
+    ), [campaignCreator]);
+
+    // The list is uploaded once in two chunks before the campaign starts
+    let chunks = [[0, 1], [2, 3]];
+    for (let [chunkIndex, chunk] of chunks.entries()) {
+      await program.methods.uploadRecipients(
+        campaignId,
+        chunk.map(i => ({ recipient: recipients[i], amount: new anchor.BN(i % 2 + 1) })),
+        chunkIndex
+      ).accounts({
+        recipientList,
+        campaignCreator: campaignCreator.publicKey,
+        operator: operator.publicKey,
+        airdropPlatform,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([operator]).rpc();
+    }
+    await waitUntil(startingTime);
+
+    // Each chunk is then distributed on its own
+    for (let [chunkIndex, chunk] of chunks.entries()) {
+      await program.methods.distributeChunk(campaignId, chunkIndex).accounts({
+        batch: {
+          creatorAta: creatorATA,
+          mint: listToken,
+          campaignCreator: campaignCreator.publicKey,
+          operator: operator.publicKey,
+          airdropPlatform,
+          tokenProgram: TOKEN_PROGRAM_ID
+        },
+        recipientList
+      }).remainingAccounts(chunk.map(i => ({ pubkey: recipientATAs[i], isWritable: true, isSigner: false }))).signers([operator]).rpc();
+    }
+    for (let [i, recipientATA] of recipientATAs.entries()) {
+      expect((await getAccount(connection, recipientATA)).amount.toString()).to.equal(`${i % 2 + 1}`);
+    }
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+  });
+
+  it("Reject a campaign whose fee exceeds the accepted maximum", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMMXF";
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    let startingTime = (await getNow()) + MIN_START_DELAY + 60;
+    let createCampaign = (maxAcceptableFee: number) => program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      new anchor.BN(maxAcceptableFee)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // The fee of one asset is 0.1 SOL, one lamport less is refused
+    await expectError(createCampaign(99999999), "FeeExceedsMax");
+    await createCampaign(100000000);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.find(c => c.campaignId == campaignId).airdropFee.toString()).to.equal("100000000");
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Split assets of a campaign into a new one", async () => {
+    let sourceId = "01BX5ZZKBKACTAV9WEVGEMMSP1";
+    let newId = "01BX5ZZKBKACTAV9WEVGEMMSP2";
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(5),
+      isNft: false,
+      decimals: 9
+    }, {
+      kind: { token: { mint: token2.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 0
+    }, {
+      kind: { sol: {} },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      sourceId,
+      assets,
+      new anchor.BN((await getNow()) + MIN_START_DELAY + 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // The token and SOL assets move to the new campaign, which pays the fee of its two assets
+    let balanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    let movedAssets = [assets[0], assets[2]];
+    await program.methods.splitCampaign(
+      sourceId,
+      newId,
+      [new anchor.BN(2), new anchor.BN(0)]
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(movedAssets)).signers([campaignCreator]).rpc();
+    expect(balanceBefore - (await connection.getBalance(campaignCreator.publicKey))).to.equal(200000000);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let source = campaigns.find(c => c.campaignId == sourceId);
+    let split = campaigns.find(c => c.campaignId == newId);
+    expect(source.assets.length).to.equal(1);
+    expect(source.totalAvailableAssets.toString()).to.equal("1");
+    expect(split.assets.length).to.equal(2);
+    expect(split.totalAvailableAssets.toString()).to.equal("1005");
+    expect(split.airdropFee.toString()).to.equal("200000000");
+
+    for (let campaignId of [sourceId, newId]) {
+      await program.methods.cancelCampaign(campaignId).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).signers([campaignCreator]).rpc();
+    }
+  });
+
+  it("Merge two campaigns of the same creator", async () => {
+    let targetId = "01BX5ZZKBKACTAV9WEVGEMMMG1";
+    let sourceId = "01BX5ZZKBKACTAV9WEVGEMMMG2";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 60;
+    let tokenAsset = (amount: number) => ({
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 9
+    });
+    let solAsset = {
+      kind: { sol: {} },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    };
+    for (let [campaignId, assets] of [[targetId, [tokenAsset(5)]], [sourceId, [tokenAsset(3), solAsset]]] as [string, any[]][]) {
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    }
+
+    // The token amounts are summed up, so the fee of one of the three assets is refunded
+    let balanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    await program.methods.mergeCampaigns(targetId, sourceId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    expect((await connection.getBalance(campaignCreator.publicKey)) - balanceBefore).to.equal(100000000);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == sourceId)).to.equal(false);
+    let target = campaigns.find(c => c.campaignId == targetId);
+    expect(target.assets.length).to.equal(2);
+    expect(target.assets[0].availableAmount.toString()).to.equal("8");
+    expect(target.assets[1].availableAmount.toString()).to.equal("1000");
+    expect(target.totalAvailableAssets.toString()).to.equal("1008");
+    expect(target.airdropFee.toString()).to.equal("200000000");
+
+    await program.methods.cancelCampaign(targetId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Only accept NFTs whose supply is fixed", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMFXS";
+    let fixedNft = await createMint(0, 1, campaignCreator.publicKey);
+    let mintableNft = await createMint(0, 1, campaignCreator.publicKey);
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createSetAuthorityInstruction(fixedNft, defaultWallet.publicKey, AuthorityType.MintTokens, null)
+    ), []);
+    let startingTime = (await getNow()) + MIN_START_DELAY + 60;
+    let createCampaign = (nft: PublicKey) => {
+      let assets: any[] = [{
+        kind: { token: { mint: nft } },
+        availableAmount: new anchor.BN(1),
+        isNft: true,
+        decimals: 0
+      }];
+      return program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(startingTime),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    };
+
+    // An NFT that can still be minted could be inflated after the airdrop
+    await expectError(createCampaign(mintableNft), "MintableNftRejected");
+    await createCampaign(fixedNft);
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+  });
+
+  it("Keep the fees of refundable campaigns out of withdrawals", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMRSV";
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN((await getNow()) + MIN_START_DELAY + 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+
+    // The admin withdraws everything it can, the pending refund stays behind
+    await program.methods.withdrawAirdropFee().accounts({
+      recipient: recipient.publicKey,
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let platform = await program.account.airdropPlatform.fetch(airdropPlatform);
+    expect(platform.accumulatedFees.toNumber()).to.be.at.least(100000000);
+    expect(platform.accumulatedFees.toNumber()).to.equal(await refundableFees());
+
+    // So the creator still gets the whole fee back
+    let balanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([campaignCreator]).rpc();
+    expect((await connection.getBalance(campaignCreator.publicKey)) - balanceBefore).to.equal(100000000);
+  });
+
+  it("Sweep token dust once a weighted split drains a campaign", async () => {
+    let setDustThreshold = (dustThreshold: number) => program.methods.setDustThreshold(new anchor.BN(dustThreshold)).accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let weightedToken = await createMint(0, 10, campaignCreator.publicKey);
+    let dustToken = await createMint(0, 1, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(weightedToken, campaignCreator.publicKey);
+    let recipients = [...Array(3).keys()].map(() => anchor.web3.Keypair.generate().publicKey);
+    let recipientATAs = await Promise.all(recipients.map(recipient => getAssociatedTokenAddress(weightedToken, recipient)));
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMDST";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [weightedToken, dustToken].map((mint, i) => ({
+      kind: { token: { mint } },
+      availableAmount: new anchor.BN(i == 0 ? 10 : 1),
+      isNft: false,
+      decimals: 0
+    }));
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      ...recipients.map((recipient, i) => createAssociatedTokenAccountInstruction(defaultWallet.publicKey, recipientATAs[i], recipient, weightedToken)),
+      createApproveInstruction(creatorATA, airdropPlatform, campaignCreator.publicKey, 10)
+    ), [campaignCreator]);
+    await setDustThreshold(2);
+    await waitUntil(startingTime);
+
+    // Equal weights over 10 tokens leave a rounding remainder, which goes to the last recipient,
+    // and the single token left in the other asset is below the dust threshold
+    let signature = await program.methods.airdropWeighted(
+      campaignId,
+      new anchor.BN(0),
+      recipients,
+      [1, 1, 1].map(weight => new anchor.BN(weight))
+    ).accounts({
+      creatorAta: creatorATA,
+      mint: weightedToken,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts(recipientATAs.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }))).signers([operator]).rpc({ commitment: "confirmed" });
+    let shares = await Promise.all(recipientATAs.map(async recipientATA => (await getAccount(connection, recipientATA)).amount.toString()));
+    expect(shares).to.deep.equal(["3", "3", "4"]);
+    let event = (await transactionEvents(signature)).find(e => e.name == "DustSwept");
+    expect(event.data.amounts.map(amount => amount.toNumber())).to.deep.equal([0, 1]);
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+
+    // The swept dust was never moved, it stays in the creator's wallet
+    let creatorDustATA = await getAssociatedTokenAddress(dustToken, campaignCreator.publicKey);
+    expect((await getAccount(connection, creatorDustATA)).amount.toString()).to.equal("1");
+    await setDustThreshold(0);
+  });
+
+  it("Operator force-closes a running campaign and returns its deposit", async () => {
+    let depositToken = await createMint(0, 3, campaignCreator.publicKey);
+    let creatorATA = await getAssociatedTokenAddress(depositToken, campaignCreator.publicKey);
+    let vault = await getAssociatedTokenAddress(depositToken, airdropPlatform, true);
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMFCD";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 5;
+    let assets: any[] = [{
+      kind: { token: { mint: depositToken } },
+      availableAmount: new anchor.BN(3),
+      isNft: false,
+      decimals: 0
+    }];
+    await program.methods.createAirdropCampaign(
+      campaignId,
+      assets,
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      createAssociatedTokenAccountInstruction(defaultWallet.publicKey, vault, airdropPlatform, depositToken)
+    ));
+    await program.methods.depositAssets(campaignId).accounts({
+      campaignCreator: campaignCreator.publicKey,
+      airdropPlatform,
+      tokenProgram: TOKEN_PROGRAM_ID
+    }).remainingAccounts([
+      { pubkey: creatorATA, isWritable: true, isSigner: false },
+      { pubkey: depositToken, isWritable: false, isSigner: false },
+      { pubkey: vault, isWritable: true, isSigner: false }
+    ]).signers([campaignCreator]).rpc();
+    let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    let airdropFee = campaigns.find(c => c.campaignId == campaignId).airdropFee;
+    await waitUntil(startingTime);
+
+    // The deposit is locked for the creator while the campaign runs, not for a force-close
+    let creatorBalanceBefore = await connection.getBalance(campaignCreator.publicKey);
+    await program.methods.adminCloseCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      operator: operator.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts([
+      { pubkey: vault, isWritable: true, isSigner: false },
+      { pubkey: depositToken, isWritable: false, isSigner: false },
+      { pubkey: creatorATA, isWritable: true, isSigner: false }
+    ]).signers([operator]).rpc();
+    let creatorBalanceAfter = await connection.getBalance(campaignCreator.publicKey);
+    expect((await getAccount(connection, creatorATA)).amount.toString()).to.equal("3");
+    expect((await getAccount(connection, vault)).amount.toString()).to.equal("0");
+    expect((creatorBalanceAfter - creatorBalanceBefore).toString()).to.equal(airdropFee.toString());
+    campaigns = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+    expect(campaigns.some(c => c.campaignId == campaignId)).to.equal(false);
+  });
+
+  it("Keep a waived campaign free when it is updated", async () => {
+    let campaignId = "01BX5ZZKBKACTAV9WEVGEMMWVU";
+    let startingTime = (await getNow()) + MIN_START_DELAY + 60;
+    let asset = {
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1),
+      isNft: false,
+      decimals: 9
+    };
+    await program.methods.createAirdropCampaignWaived(
+      campaignId,
+      [asset],
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      operator: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints([asset])).signers([operator]).rpc();
+
+    // A second asset would cost a fee on a regular campaign, not on a waived one
+    let feesBefore = (await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees;
+    await program.methods.updateCampaign(
+      campaignId,
+      [asset, asset],
+      new anchor.BN(startingTime),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints([asset, asset])).signers([operator]).rpc();
+    let platform: any = await program.account.airdropPlatform.fetch(airdropPlatform);
+    expect(platform.accumulatedFees.toString()).to.equal(feesBefore.toString());
+    let campaign = platform.allCampaigns.find(c => c.campaignId == campaignId);
+    expect(campaign.airdropFee.toString()).to.equal("0");
+    expect(campaign.assets.length).to.equal(2);
+
+    await program.methods.cancelCampaign(campaignId).accounts({
+      airdropPlatform,
+      campaignCreator: operator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([operator]).rpc();
+  });
+
+  it("Set and read back campaign metadata URI", async () => {
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    let fetchMetadataUri = async () => {
+      let campaigns: any = (await program.account.airdropPlatform.fetch(airdropPlatform)).allCampaigns;
+      return campaigns.find(c => c.campaignId == "01BX5ZZKBKACTAV9WEVGEMMURI")?.metadataUri;
+    };
+    await expectError(
+      program.methods.createAirdropCampaign(
+        "01BX5ZZKBKACTAV9WEVGEMMURI",
+        assets,
+        new anchor.BN(now + 30 * 60),
+        new anchor.BN(0),
+        "https://playlink.network/" + "x".repeat(200),
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc(),
+      "MetadataUriTooLong"
+    );
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMURI",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      "https://playlink.network/campaigns/1.json",
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    expect(await fetchMetadataUri()).to.equal("https://playlink.network/campaigns/1.json");
+    await program.methods.updateCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMURI",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      "https://playlink.network/campaigns/2.json",
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    expect(await fetchMetadataUri()).to.equal("https://playlink.network/campaigns/2.json");
+  });
+
+  it("Campaign counter increments on each create", async () => {
+    let now = await getNow();
+    let assets: any[] = [{
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(1000),
+      isNft: false,
+      decimals: 9
+    }];
+    let counterBefore = (await program.account.airdropPlatform.fetch(airdropPlatform)).campaignCounter;
+    for (let campaignId of ["01BX5ZZKBKACTAV9WEVGEMMCT1", ""]) {
+      await program.methods.createAirdropCampaign(
+        campaignId,
+        assets,
+        new anchor.BN(now + 30 * 60),
+        new anchor.BN(0),
+        null,
+        null,
+        new anchor.BN(0),
+        null
+      ).accounts({
+        airdropPlatform,
+        campaignCreator: campaignCreator.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    }
+    let platform = await program.account.airdropPlatform.fetch(airdropPlatform);
+    expect(platform.campaignCounter.sub(counterBefore).toString()).to.equal("2");
+    // An empty id is generated from the counter
+    let campaigns: any = platform.allCampaigns;
+    expect(campaigns.some(c => c.campaignId == platform.campaignCounter.toString())).to.equal(true);
+  });
+
+  it("Clear all operators except the admin", async () => {
+    let extraOperators = [...Array(3)].map(() => anchor.web3.Keypair.generate().publicKey);
+    await program.methods.setOperators(extraOperators, extraOperators.map(() => true)).accounts({
+      airdropPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    expect((await program.account.airdropPlatform.fetch(airdropPlatform)).operators.length).to.equal(5);
+
+    await program.methods.clearOperators().accounts({
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let operators = (await program.account.airdropPlatform.fetch(airdropPlatform)).operators;
+    expect(operators.length).to.equal(1);
+    expect(operators[0].toString()).to.equal(admin.publicKey.toString());
+
+    // Restore the operator for the following tests
+    await program.methods.setOperators([operator.publicKey], [true]).accounts({
+      airdropPlatform,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+  });
+
+  it("Account fees on collection and refund", async () => {
+    let accumulatedFees = async () => (await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees;
+    let now = await getNow();
+    let assets: any[] = [1000, 2000].map(amount => ({
+      kind: { token: { mint: token1.publicKey } },
+      availableAmount: new anchor.BN(amount),
+      isNft: false,
+      decimals: 9
+    }));
+    let before = await accumulatedFees();
+    await program.methods.createAirdropCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMACC",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0),
+      null
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    expect((await accumulatedFees()).sub(before).toString()).to.equal("200000000");
+
+    assets = assets.slice(0, 1);
+    await program.methods.updateCampaign(
+      "01BX5ZZKBKACTAV9WEVGEMMACC",
+      assets,
+      new anchor.BN(now + 30 * 60),
+      new anchor.BN(0),
+      null,
+      null,
+      new anchor.BN(0)
+    ).accounts({
+      airdropPlatform,
+      campaignCreator: campaignCreator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).remainingAccounts(assetMints(assets)).signers([campaignCreator]).rpc();
+    expect((await accumulatedFees()).sub(before).toString()).to.equal("100000000");
+  });
+
+  it("Admin withdraws airdrop fee", async () => {
+    // Lamports sent to the platform outside of fee collection are not fees
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.transfer({ fromPubkey: defaultWallet.publicKey, toPubkey: airdropPlatform, lamports: 12345 })
+    ));
+    let accumulatedFees = (await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees;
+    let recipientBalanceBefore = await connection.getBalance(recipient.publicKey);
+    await program.methods.withdrawAirdropFee().accounts({
+      recipient: recipient.publicKey,
+      admin: admin.publicKey,
+      airdropPlatform,
+      systemProgram: anchor.web3.SystemProgram.programId
+    }).signers([admin]).rpc();
+    let recipientBalanceAfter = await connection.getBalance(recipient.publicKey);
+    let airdropPlatformBalance = await connection.getBalance(airdropPlatform);
+    let rentExemptMinimum = await connection.getMinimumBalanceForRentExemption(10240);
+
+    // Only the fees creators may still get back stay behind
+    let refundable = await refundableFees();
+    expect((await program.account.airdropPlatform.fetch(airdropPlatform)).accumulatedFees.toNumber()).to.equal(refundable);
+    expect((recipientBalanceAfter - recipientBalanceBefore).toString()).to.equal(accumulatedFees.sub(new anchor.BN(refundable)).toString());
+    expect(airdropPlatformBalance.toString()).to.equal((rentExemptMinimum + 12345 + refundable).toString());
+  });
+});
 
 let sleep = (seconds: number) => {
   return new Promise(resolve => setTimeout(resolve, seconds * 1000));
-};
\ No newline at end of file
+};
+
+let expectError = async (promise: Promise<any>, code: string) => {
+  try {
+    await promise;
+  } catch (err) {
+    let message = err?.error?.errorCode?.code ?? `${err?.logs?.join("\n")}\n${err}`;
+    expect(message).to.include(code);
+    return;
+  }
+  expect.fail(`Expected error ${code}`);
+};